@@ -0,0 +1,152 @@
+//! 3次元累積和
+//!
+//! ## Example
+//! ```
+//! use kyopro_accumlate2d::accumulate3d::Accumulate3D;
+//! let mut acc = Accumulate3D::new(2, 2, 2, 0i32);
+//! acc.add(0, 1, 1, 3);
+//! acc.add(1, 1, 0, 4);
+//! acc.build();
+//! assert_eq!(acc.query(0, 2, 0, 2, 0, 2), 7);
+//! assert_eq!(acc.query(0, 1, 0, 2, 0, 2), 3);
+//! ```
+use std::ops::{Add, AddAssign, Sub};
+
+pub struct Accumulate3D<T> {
+    x: usize,
+    y: usize,
+    z: usize,
+    a: Vec<Vec<Vec<T>>>,
+    d: Vec<Vec<Vec<T>>>,
+    built: bool,
+}
+impl<T> Accumulate3D<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + AddAssign,
+{
+    /// init には 0 (加法の単位元) を指定します
+    pub fn new(x: usize, y: usize, z: usize, init: T) -> Self {
+        let a = vec![vec![vec![init; z]; y]; x];
+        let d = vec![vec![vec![init; z + 1]; y + 1]; x + 1];
+        Accumulate3D {
+            x,
+            y,
+            z,
+            a,
+            d,
+            built: false,
+        }
+    }
+    /// 反映するには `build` を呼ぶ必要があります
+    pub fn add(&mut self, i: usize, j: usize, k: usize, v: T) {
+        self.built = false;
+        self.a[i][j][k] += v;
+    }
+    /// 反映するには `build` を呼ぶ必要があります
+    pub fn set(&mut self, i: usize, j: usize, k: usize, v: T) {
+        self.built = false;
+        self.a[i][j][k] = v;
+    }
+    /// O(XYZ)
+    pub fn build(&mut self) {
+        for i in 0..self.x {
+            for j in 0..self.y {
+                for k in 0..self.z {
+                    let d = &self.d;
+                    // 符号なし整数でも途中で負にならないように、正の項を先に足す
+                    self.d[i + 1][j + 1][k + 1] = self.a[i][j][k]
+                        + d[i][j + 1][k + 1]
+                        + d[i + 1][j][k + 1]
+                        + d[i + 1][j + 1][k]
+                        + d[i][j][k]
+                        - d[i][j][k + 1]
+                        - d[i][j + 1][k]
+                        - d[i + 1][j][k];
+                }
+            }
+        }
+        self.built = true;
+    }
+    /// [x1, x2) x [y1, y2) x [z1, z2) の累積和を求めます
+    pub fn query(&self, x1: usize, x2: usize, y1: usize, y2: usize, z1: usize, z2: usize) -> T {
+        assert!(self.built, "call build before query");
+        let d = &self.d;
+        // 符号なし整数でも途中で負にならないように、正の項を先に足す
+        d[x2][y2][z2] + d[x1][y1][z2] + d[x1][y2][z1] + d[x2][y1][z1]
+            - d[x1][y2][z2]
+            - d[x2][y1][z2]
+            - d[x2][y2][z1]
+            - d[x1][y1][z1]
+    }
+}
+
+#[test]
+fn test_accumulate3d() {
+    let (x, y, z) = (3, 4, 2);
+    let mut acc = Accumulate3D::new(x, y, z, 0i64);
+    let mut a: Vec<Vec<Vec<i64>>> = (0..x)
+        .map(|i| (0..y).map(|j| (0..z).map(|k| (i * 7 + j * 3 + k) as i64 % 5 - 2).collect()).collect())
+        .collect();
+    for (i, p) in a.iter().enumerate() {
+        for (j, q) in p.iter().enumerate() {
+            for (k, &v) in q.iter().enumerate() {
+                acc.set(i, j, k, v);
+            }
+        }
+    }
+    acc.add(2, 3, 1, 10);
+    a[2][3][1] += 10;
+    acc.build();
+    for x1 in 0..=x {
+        for x2 in x1..=x {
+            for y1 in 0..=y {
+                for y2 in y1..=y {
+                    for z1 in 0..=z {
+                        for z2 in z1..=z {
+                            let naive: i64 = a[x1..x2]
+                                .iter()
+                                .flat_map(|p| p[y1..y2].iter().flat_map(|q| q[z1..z2].iter()))
+                                .sum();
+                            assert_eq!(acc.query(x1, x2, y1, y2, z1, z2), naive);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_accumulate3d_unsigned() {
+    let (x, y, z) = (3, 3, 3);
+    let mut acc = Accumulate3D::new(x, y, z, 0u64);
+    for i in 0..x {
+        for j in 0..y {
+            for k in 0..z {
+                acc.set(i, j, k, (i * 9 + j * 3 + k) as u64);
+            }
+        }
+    }
+    acc.build();
+    for x1 in 0..=x {
+        for x2 in x1..=x {
+            for y1 in 0..=y {
+                for y2 in y1..=y {
+                    for z1 in 0..=z {
+                        for z2 in z1..=z {
+                            let mut naive = 0;
+                            for i in x1..x2 {
+                                for j in y1..y2 {
+                                    for k in z1..z2 {
+                                        naive += (i * 9 + j * 3 + k) as u64;
+                                    }
+                                }
+                            }
+                            assert_eq!(acc.query(x1, x2, y1, y2, z1, z2), naive);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! 1次元の累積和・いもす法
+//!
+//! ## Example
+//! ```
+//! use kyopro_accumlate2d::imos::{prefix_sums, Imos};
+//! let s = prefix_sums(&[3, 1, 4, 1, 5], 0);
+//! // [1, 4) の和
+//! assert_eq!(s[4] - s[1], 6);
+//!
+//! let mut imos = Imos::new(5, 0);
+//! imos.add(1, 4, 2);
+//! imos.add(0, 2, 1);
+//! assert_eq!(imos.build(), vec![1, 3, 2, 2, 0]);
+//! ```
+use std::ops::{Add, AddAssign, Neg};
+
+/// 累積和を返します
+/// 戻り値 `s` の長さは `a.len() + 1` で、[l, r) の和は `s[r] - s[l]` です
+/// init には 0 (加法の単位元) を指定します
+pub fn prefix_sums<T: Copy + Add<Output = T>>(a: &[T], init: T) -> Vec<T> {
+    let mut s = Vec::with_capacity(a.len() + 1);
+    s.push(init);
+    for &x in a {
+        s.push(*s.last().unwrap() + x);
+    }
+    s
+}
+
+/// いもす法
+/// 区間加算をまとめて行い、`build` で各要素の値を求めます
+///
+/// 差分に負の値を持つので、`T` は符号付きの型である必要があります
+/// ```compile_fail
+/// use kyopro_accumlate2d::imos::Imos;
+/// let mut imos = Imos::new(3, 0u64);
+/// imos.add(1, 2, 1);
+/// ```
+pub struct Imos<T> {
+    diff: Vec<T>,
+    init: T,
+}
+impl<T> Imos<T>
+where
+    T: Copy + Add<Output = T> + Neg<Output = T> + AddAssign,
+{
+    /// init には 0 (加法の単位元) を指定します
+    pub fn new(n: usize, init: T) -> Self {
+        Imos {
+            diff: vec![init; n + 1],
+            init,
+        }
+    }
+    /// [l, r) の各要素に v を加算します
+    /// O(1)
+    pub fn add(&mut self, l: usize, r: usize, v: T) {
+        assert!(l <= r && r < self.diff.len());
+        self.diff[l] += v;
+        self.diff[r] += -v;
+    }
+    /// 各要素の値を返します
+    /// O(n)
+    pub fn build(&self) -> Vec<T> {
+        let n = self.diff.len() - 1;
+        let mut res = Vec::with_capacity(n);
+        let mut cur = self.init;
+        for &d in &self.diff[..n] {
+            cur += d;
+            res.push(cur);
+        }
+        res
+    }
+}
+
+#[test]
+fn test_imos() {
+    let n = 6;
+    let mut imos = Imos::new(n, 0i64);
+    let mut a = vec![0i64; n];
+    for (l, r, v) in [(0, 6, 2), (2, 5, -3), (5, 6, 7), (3, 3, 100)] {
+        imos.add(l, r, v);
+        for x in &mut a[l..r] {
+            *x += v;
+        }
+    }
+    let b = imos.build();
+    assert_eq!(b, a);
+    let s = prefix_sums(&b, 0);
+    for l in 0..=n {
+        for r in l..=n {
+            assert_eq!(s[r] - s[l], a[l..r].iter().sum::<i64>());
+        }
+    }
+}
+
+#[test]
+fn test_prefix_sums_unsigned() {
+    let a = [3u64, 0, 4, 1, 5];
+    let s = prefix_sums(&a, 0);
+    for l in 0..=a.len() {
+        for r in l..=a.len() {
+            assert_eq!(s[r] - s[l], a[l..r].iter().sum::<u64>());
+        }
+    }
+}
@@ -1,40 +1,120 @@
-///! 2次元累積和
-use std::ops::{Add, AddAssign, Sub};
+//! 2次元累積和
+//!
+//! - `Accumulate2D`: 2次元累積和 (矩形への区間加算もできます)
+//! - `accumulate3d::Accumulate3D`: 3次元累積和
+//! - `imos::Imos`: 1次元の累積和・いもす法
+//!
+//! 値を更新しながらクエリに答える場合は `kyopro_fenwick::fenwick2d::Fenwick2D` を使います
+pub mod accumulate3d;
+pub mod imos;
+
+use std::ops::{Add, AddAssign, Neg, Sub};
 
 pub struct Accumulate2D<T> {
     h: usize,
     w: usize,
     a: Vec<Vec<T>>,
     d: Vec<Vec<T>>,
+    // 矩形加算の差分
+    diff: Vec<Vec<T>>,
+    init: T,
+    built: bool,
 }
 impl<T> Accumulate2D<T>
 where
     T: Copy + Add<Output = T> + Sub<Output = T> + AddAssign,
 {
+    /// init には 0 (加法の単位元) を指定します
     pub fn new(h: usize, w: usize, init: T) -> Self {
         let a = vec![vec![init; w]; h];
         let s = vec![vec![init; w + 1]; h + 1];
-        Accumulate2D { h, w, a, d: s }
+        let diff = vec![vec![init; w + 1]; h + 1];
+        Accumulate2D {
+            h,
+            w,
+            a,
+            d: s,
+            diff,
+            init,
+            built: false,
+        }
     }
+    /// 反映するには `build` を呼ぶ必要があります
     pub fn add(&mut self, r: usize, c: usize, v: T) {
+        self.built = false;
         self.a[r][c] += v;
     }
+    /// 反映するには `build` を呼ぶ必要があります
     pub fn set(&mut self, r: usize, c: usize, v: T) {
+        self.built = false;
         self.a[r][c] = v;
     }
+    /// O(HW)
     pub fn build(&mut self) {
+        // 矩形加算の差分を各要素に反映する
+        for i in 0..=self.h {
+            for j in 0..self.w {
+                let x = self.diff[i][j];
+                self.diff[i][j + 1] += x;
+            }
+        }
+        for i in 0..self.h {
+            for j in 0..=self.w {
+                let x = self.diff[i][j];
+                self.diff[i + 1][j] += x;
+            }
+        }
+        for i in 0..self.h {
+            for j in 0..self.w {
+                self.a[i][j] += self.diff[i][j];
+            }
+        }
+        for row in &mut self.diff {
+            row.fill(self.init);
+        }
+
         for i in 0..self.h {
             for j in 0..self.w {
                 self.d[i + 1][j + 1] = self.a[i][j] + self.d[i + 1][j] + self.d[i][j + 1] - self.d[i][j];
             }
         }
+        self.built = true;
+    }
+    /// (r, c) の値を返します
+    pub fn get(&self, r: usize, c: usize) -> T {
+        assert!(self.built, "call build before get");
+        self.a[r][c]
     }
     /// 左上座標 (t, l) から 右下座標 (b, r) の範囲の累積和を求めます (右下は半開区間)
     /// 範囲指定順注意 [t, b), [l, r) の順で指定する
     pub fn query(&self, t: usize, b: usize, l: usize, r: usize) -> T {
+        assert!(self.built, "call build before query");
         self.d[b][r] + self.d[t][l] - self.d[b][l] - self.d[t][r]
     }
 }
+impl<T> Accumulate2D<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + AddAssign + Neg<Output = T>,
+{
+    /// 左上座標 (t, l) から 右下座標 (b, r) の範囲の各要素に v を加算します (右下は半開区間)
+    /// 範囲指定順注意 [t, b), [l, r) の順で指定する
+    /// 2次元いもす法で、反映するには `build` を呼ぶ必要があります
+    /// 差分に負の値を持つので、符号付きの型でのみ使えます
+    /// ```compile_fail
+    /// use kyopro_accumlate2d::Accumulate2D;
+    /// let mut acc = Accumulate2D::new(2, 2, 0u64);
+    /// acc.range_add(0, 1, 0, 1, 1);
+    /// ```
+    /// O(1)
+    pub fn range_add(&mut self, t: usize, b: usize, l: usize, r: usize, v: T) {
+        assert!(t <= b && b <= self.h && l <= r && r <= self.w);
+        self.built = false;
+        self.diff[t][l] += v;
+        self.diff[b][r] += v;
+        self.diff[t][r] += -v;
+        self.diff[b][l] += -v;
+    }
+}
 
 #[test]
 fn test_accumurate() {
@@ -51,3 +131,64 @@ fn test_accumurate() {
     assert_eq!(acc.query(0, 1, 0, 3), 3);
     assert_eq!(acc.query(1, 3, 1, 3), 24);
 }
+
+#[test]
+fn test_range_add() {
+    let (h, w) = (4, 5);
+    let mut acc = Accumulate2D::new(h, w, 0i64);
+    let mut a = vec![vec![0i64; w]; h];
+    acc.add(1, 1, 5);
+    a[1][1] += 5;
+    for (t, b, l, r, v) in [(0, 4, 0, 5, 1), (1, 3, 2, 4, 10), (3, 4, 4, 5, -7), (2, 2, 0, 5, 100)] {
+        acc.range_add(t, b, l, r, v);
+        for row in &mut a[t..b] {
+            for x in &mut row[l..r] {
+                *x += v;
+            }
+        }
+    }
+    acc.build();
+    for (i, row) in a.iter().enumerate() {
+        for (j, &x) in row.iter().enumerate() {
+            assert_eq!(acc.get(i, j), x);
+        }
+    }
+    assert_eq!(acc.query(0, h, 0, w), a.iter().flatten().sum::<i64>());
+
+    // build 後の加算は次の build で反映される
+    acc.range_add(0, 1, 0, 2, 3);
+    acc.build();
+    assert_eq!(acc.get(0, 1), a[0][1] + 3);
+    assert_eq!(acc.get(1, 1), a[1][1]);
+}
+
+#[test]
+fn test_accumulate_unsigned() {
+    let (h, w) = (3, 4);
+    let mut acc = Accumulate2D::new(h, w, 0u64);
+    for i in 0..h {
+        for j in 0..w {
+            acc.set(i, j, (i * w + j) as u64);
+        }
+    }
+    acc.build();
+    for t in 0..=h {
+        for b in t..=h {
+            for l in 0..=w {
+                for r in l..=w {
+                    let naive: u64 = (t..b).flat_map(|i| (l..r).map(move |j| (i * w + j) as u64)).sum();
+                    assert_eq!(acc.query(t, b, l, r), naive);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_query_before_build() {
+    let mut acc = Accumulate2D::new(2, 2, 0i32);
+    acc.build();
+    acc.add(0, 0, 1);
+    acc.query(0, 1, 0, 1);
+}
@@ -0,0 +1,264 @@
+//! Binary Trie です
+//! 非負整数の多重集合を管理します
+//!
+//! 整数キーの場合、`Multiset` の代わりに使うことができます。
+//! xor に関するクエリ (x との xor の最小値など) にも対応しています。
+//!
+//! ## Example
+//! ```
+//! use kyopro_data_stractures::binary_trie::BinaryTrie;
+//! // 30 bit の値を管理する
+//! let mut bt = BinaryTrie::new(30);
+//! bt.insert(3);
+//! bt.insert(5);
+//! bt.insert(5);
+//! assert_eq!(bt.len(), 3);
+//! assert_eq!(bt.kth_smallest(1), Some(5));
+//! assert_eq!(bt.count_less_than(5), 1);
+//! // 6^5 = 3, 6^3 = 5
+//! assert_eq!(bt.min_xor(6), Some(3));
+//! ```
+
+/// 遷移先が存在しないことを表す値
+const NONE: usize = !0;
+
+#[derive(Clone, Debug)]
+pub struct BinaryTrie {
+    bits: usize,
+    next: Vec<[usize; 2]>,
+    /// ノードを通る値の個数
+    cnt: Vec<usize>,
+}
+
+impl BinaryTrie {
+    /// + `bits`: 管理する値の bit 幅 (`1..=64`)
+    pub fn new(bits: usize) -> Self {
+        assert!(0 < bits && bits <= 64);
+        BinaryTrie { bits, next: vec![[NONE; 2]], cnt: vec![0] }
+    }
+
+    #[inline]
+    fn bit(&self, x: u64, i: usize) -> usize {
+        (x >> i & 1) as usize
+    }
+
+    /// 登録要素数を返します
+    pub fn len(&self) -> usize {
+        self.cnt[0]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 要素を追加します
+    /// O(bits)
+    pub fn insert(&mut self, x: u64) {
+        assert!(self.bits == 64 || x >> self.bits == 0);
+        let mut v = 0;
+        self.cnt[0] += 1;
+        for i in (0..self.bits).rev() {
+            let b = self.bit(x, i);
+            if self.next[v][b] == NONE {
+                self.next[v][b] = self.next.len();
+                self.next.push([NONE; 2]);
+                self.cnt.push(0);
+            }
+            v = self.next[v][b];
+            self.cnt[v] += 1;
+        }
+    }
+
+    /// 要素を 1 つ削除します
+    /// 存在しない場合は何もせず false を返します
+    /// O(bits)
+    pub fn erase(&mut self, x: u64) -> bool {
+        if self.count(x) == 0 {
+            return false;
+        }
+        let mut v = 0;
+        self.cnt[0] -= 1;
+        for i in (0..self.bits).rev() {
+            v = self.next[v][self.bit(x, i)];
+            self.cnt[v] -= 1;
+        }
+        true
+    }
+
+    /// 要素 x の個数を返します
+    pub fn count(&self, x: u64) -> usize {
+        if self.bits < 64 && x >> self.bits != 0 {
+            return 0;
+        }
+        let mut v = 0;
+        for i in (0..self.bits).rev() {
+            v = self.next[v][self.bit(x, i)];
+            if v == NONE {
+                return 0;
+            }
+        }
+        self.cnt[v]
+    }
+
+    /// 要素 x が存在するかどうかを返します
+    pub fn contains(&self, x: u64) -> bool {
+        self.count(x) > 0
+    }
+
+    /// 子 `u` が空でないか
+    #[inline]
+    fn exists(&self, u: usize) -> bool {
+        u != NONE && self.cnt[u] > 0
+    }
+
+    /// 管理する bit 幅の部分を取り出すマスク
+    #[inline]
+    fn mask(&self) -> u64 {
+        if self.bits == 64 {
+            !0
+        } else {
+            (1 << self.bits) - 1
+        }
+    }
+
+    /// 各要素と x の xor のうち最小値を返します
+    /// x は `bits` bit を超えていても構いません
+    /// 空の場合は None です
+    pub fn min_xor(&self, x: u64) -> Option<u64> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut v = 0;
+        // 要素は bits bit に収まるので、x の上位 bit はそのまま残る
+        let mut res = x & !self.mask();
+        for i in (0..self.bits).rev() {
+            let b = self.bit(x, i);
+            if self.exists(self.next[v][b]) {
+                v = self.next[v][b];
+            } else {
+                v = self.next[v][b ^ 1];
+                res |= 1 << i;
+            }
+        }
+        Some(res)
+    }
+
+    /// 各要素と x の xor のうち最大値を返します
+    /// x は `bits` bit を超えていても構いません
+    /// 空の場合は None です
+    pub fn max_xor(&self, x: u64) -> Option<u64> {
+        let mask = self.mask();
+        self.min_xor(!x & mask).map(|v| (v ^ mask) | (x & !mask))
+    }
+
+    /// 小さい方から `k` 番目 (0-indexed) の要素を返します
+    /// 存在しない場合は None です
+    pub fn kth_smallest(&self, k: usize) -> Option<u64> {
+        if k >= self.len() {
+            return None;
+        }
+        let mut k = k;
+        let mut v = 0;
+        let mut res = 0;
+        for i in (0..self.bits).rev() {
+            let l = self.next[v][0];
+            let lc = if l == NONE { 0 } else { self.cnt[l] };
+            if k < lc {
+                v = l;
+            } else {
+                k -= lc;
+                v = self.next[v][1];
+                res |= 1 << i;
+            }
+        }
+        Some(res)
+    }
+
+    /// x 未満の要素数を返します
+    pub fn count_less_than(&self, x: u64) -> usize {
+        if self.bits < 64 && x >> self.bits != 0 {
+            return self.len();
+        }
+        let mut v = 0;
+        let mut res = 0;
+        for i in (0..self.bits).rev() {
+            let b = self.bit(x, i);
+            if b == 1 && self.next[v][0] != NONE {
+                res += self.cnt[self.next[v][0]];
+            }
+            v = self.next[v][b];
+            if v == NONE {
+                break;
+            }
+        }
+        res
+    }
+
+    /// 最小値を返します
+    pub fn get_min(&self) -> Option<u64> {
+        self.kth_smallest(0)
+    }
+
+    /// 最大値を返します
+    pub fn get_max(&self) -> Option<u64> {
+        if self.is_empty() {
+            None
+        } else {
+            self.kth_smallest(self.len() - 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinaryTrie;
+
+    #[test]
+    fn test_naive() {
+        let vals = [5u64, 3, 12, 5, 0, 7, 15, 9, 3];
+        let mut bt = BinaryTrie::new(4);
+        let mut v = vec![];
+        for &x in &vals {
+            bt.insert(x);
+            v.push(x);
+            v.sort();
+            for x in 0..16 {
+                assert_eq!(bt.count(x), v.iter().filter(|&&y| y == x).count());
+                assert_eq!(bt.count_less_than(x), v.iter().filter(|&&y| y < x).count());
+                assert_eq!(bt.min_xor(x), v.iter().map(|&y| y ^ x).min());
+                assert_eq!(bt.max_xor(x), v.iter().map(|&y| y ^ x).max());
+            }
+            for k in 0..=v.len() {
+                assert_eq!(bt.kth_smallest(k), v.get(k).copied());
+            }
+        }
+        assert_eq!(bt.count_less_than(100), vals.len());
+        assert_eq!(bt.get_min(), Some(0));
+        assert_eq!(bt.get_max(), Some(15));
+        // bits を超える x
+        for x in [16u64, 21, 100, 1 << 40 | 6, !0] {
+            assert_eq!(bt.min_xor(x), vals.iter().map(|&y| y ^ x).min());
+            assert_eq!(bt.max_xor(x), vals.iter().map(|&y| y ^ x).max());
+        }
+    }
+
+    #[test]
+    fn test_erase() {
+        let mut bt = BinaryTrie::new(64);
+        bt.insert(!0);
+        bt.insert(1);
+        bt.insert(1);
+        assert!(bt.erase(1));
+        assert!(bt.contains(1));
+        assert!(bt.erase(1));
+        assert!(!bt.contains(1));
+        assert!(!bt.erase(1));
+        assert!(!bt.erase(2));
+        assert_eq!(bt.len(), 1);
+        assert_eq!(bt.get_min(), Some(!0));
+        assert_eq!(bt.min_xor(0), Some(!0));
+        bt.erase(!0);
+        assert_eq!(bt.get_max(), None);
+        assert_eq!(bt.min_xor(0), None);
+    }
+}
@@ -1,43 +1,109 @@
 //! Set, Map に lower_bound ライクなメソッドを追加する trait です
 //! original: https://github.com/yiolino/atcoder-rust#btreeset
 //!
-use std::collections::{BTreeMap, BTreeSet};
+//! `BTreeSet` には `Neighbors`、`BTreeMap` には `MapNeighbors` と `NeighborsMut` を実装しています。
+//! `BTreeSet` では要素 `&T`、`BTreeMap` では `(&K, &V)` を返します。
+//!
+//! ## Example
+//! ```
+//! use kyopro_data_stractures::btree_neighbors::{MapNeighbors, Neighbors};
+//! use std::collections::{BTreeMap, BTreeSet};
+//! let set = BTreeSet::from([2, 4, 8]);
+//! assert_eq!(set.find_first(&4), Some(&4));
+//! assert_eq!(set.find_next(&4), Some(&8));
+//! assert_eq!(set.find_prev(&4), Some(&2));
+//! assert_eq!(set.nearest(&6), Some(&4));
+//!
+//! let mp = BTreeMap::from([(2, 'a'), (4, 'b')]);
+//! assert_eq!(mp.find_next(&2), Some((&4, &'b')));
+//! ```
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::{Bound, Sub},
+};
 
-pub trait Neighbors<T> {
-    /// x 以上の最小値を検索
-    fn find_first(&self, x: &T) -> Option<&T>;
-    /// x 以下の最大値を検索
-    fn find_last(&self, x: &T) -> Option<&T>;
+/// x 以下の最大値 `lo` と x 以上の最小値 `hi` のうち、x との差が小さい方を返します
+/// 差が同じ場合は小さい方を返します
+fn nearest_of<K, I>(x: &K, lo: Option<I>, hi: Option<I>, key: impl Fn(&I) -> &K) -> Option<I>
+where
+    K: Copy + Sub<Output = K> + Ord,
+{
+    match (lo, hi) {
+        (Some(a), Some(b)) => {
+            if *x - *key(&a) <= *key(&b) - *x {
+                Some(a)
+            } else {
+                Some(b)
+            }
+        }
+        (a, b) => a.or(b),
+    }
 }
 
-impl<T: Ord> Neighbors<T> for BTreeSet<T> {
-    fn find_first(&self, x: &T) -> Option<&T> {
-        let mut aftr = self.range((std::ops::Bound::Included(x), std::ops::Bound::Unbounded));
-        aftr.next()
-    }
-    fn find_last(&self, x: &T) -> Option<&T> {
-        let mut bfr = self.range((std::ops::Bound::Unbounded, std::ops::Bound::Included(x)));
-        bfr.next_back()
-    }
+// Set と Map で戻り値の型だけが違うので、trait の定義と実装を 1 つの本体から生成する
+// `[mut]` を指定すると `&mut self` を取り、`range` には `range_mut` などの範囲取得メソッドを指定する
+macro_rules! impl_neighbors {
+    (
+        $(#[$attr:meta])*
+        pub trait $name:ident<$k:ident $(, $v:ident)?> for $ty:ty;
+        fn [$($m:tt)?] $first:ident, $last:ident, $next:ident, $prev:ident -> $item:ty = $range:ident;
+        $(fn nearest = $key_of:expr;)?
+    ) => {
+        $(#[$attr])*
+        pub trait $name<$k $(, $v)?> {
+            /// x 以上の最小値を検索
+            fn $first(&$($m)? self, x: &$k) -> Option<$item>;
+            /// x 以下の最大値を検索
+            fn $last(&$($m)? self, x: &$k) -> Option<$item>;
+            /// x より大きい最小値を検索
+            fn $next(&$($m)? self, x: &$k) -> Option<$item>;
+            /// x より小さい最大値を検索
+            fn $prev(&$($m)? self, x: &$k) -> Option<$item>;
+            $(
+                /// x との差が最小の値を検索
+                /// 差が同じ場合は小さい方を返します
+                fn nearest(&self, x: &$k) -> Option<$item>
+                where
+                    $k: Copy + Sub<Output = $k> + Ord,
+                {
+                    nearest_of(x, self.$last(x), self.$first(x), $key_of)
+                }
+            )?
+        }
+
+        impl<$k: Ord $(, $v)?> $name<$k $(, $v)?> for $ty {
+            fn $first(&$($m)? self, x: &$k) -> Option<$item> {
+                self.$range((Bound::Included(x), Bound::Unbounded)).next()
+            }
+            fn $last(&$($m)? self, x: &$k) -> Option<$item> {
+                self.$range((Bound::Unbounded, Bound::Included(x))).next_back()
+            }
+            fn $next(&$($m)? self, x: &$k) -> Option<$item> {
+                self.$range((Bound::Excluded(x), Bound::Unbounded)).next()
+            }
+            fn $prev(&$($m)? self, x: &$k) -> Option<$item> {
+                self.$range((Bound::Unbounded, Bound::Excluded(x))).next_back()
+            }
+        }
+    };
 }
 
-// HACK: set とひとつにまとめられないか？
-pub trait MapNeighbors<K, V> {
-    /// x 以上の最小値を取得
-    fn find_first(&self, x: &K) -> Option<(&K, &V)>;
-    /// x 以下の最大値を取得
-    fn find_last(&self, x: &K) -> Option<(&K, &V)>;
+impl_neighbors! {
+    pub trait Neighbors<T> for BTreeSet<T>;
+    fn [] find_first, find_last, find_next, find_prev -> &T = range;
+    fn nearest = |&v| v;
 }
-impl<K: Ord, V> MapNeighbors<K, V> for BTreeMap<K, V> {
-    fn find_first(&self, x: &K) -> Option<(&K, &V)> {
-        let mut aftr = self.range((std::ops::Bound::Included(x), std::ops::Bound::Unbounded));
-        aftr.next()
-    }
 
-    fn find_last(&self, x: &K) -> Option<(&K, &V)> {
-        let mut bfr = self.range((std::ops::Bound::Unbounded, std::ops::Bound::Included(x)));
-        bfr.next_back()
-    }
+impl_neighbors! {
+    pub trait MapNeighbors<K, V> for BTreeMap<K, V>;
+    fn [] find_first, find_last, find_next, find_prev -> (&K, &V) = range;
+    fn nearest = |&(k, _)| k;
+}
+
+impl_neighbors! {
+    /// Map の値を書き換えられる検索です
+    pub trait NeighborsMut<K, V> for BTreeMap<K, V>;
+    fn [mut] find_first_mut, find_last_mut, find_next_mut, find_prev_mut -> (&K, &mut V) = range_mut;
 }
 
 #[cfg(test)]
@@ -85,4 +151,53 @@ mod tests {
         assert_eq!(mp.find_last(&2), Some((&2, &1)));
         assert_eq!(mp.find_last(&1), None);
     }
+
+    #[test]
+    fn test_strict() {
+        let set = BTreeSet::from([2, 4, 6]);
+        assert_eq!(set.find_next(&4), Some(&6));
+        assert_eq!(set.find_next(&5), Some(&6));
+        assert_eq!(set.find_next(&6), None);
+        assert_eq!(set.find_prev(&4), Some(&2));
+        assert_eq!(set.find_prev(&3), Some(&2));
+        assert_eq!(set.find_prev(&2), None);
+
+        let mp = BTreeMap::from([(2, 'a'), (4, 'b')]);
+        assert_eq!(mp.find_next(&2), Some((&4, &'b')));
+        assert_eq!(mp.find_prev(&4), Some((&2, &'a')));
+        assert_eq!(mp.find_prev(&2), None);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let set = BTreeSet::from([2i32, 5, 9]);
+        assert_eq!(set.nearest(&0), Some(&2));
+        assert_eq!(set.nearest(&5), Some(&5));
+        assert_eq!(set.nearest(&7), Some(&5));
+        assert_eq!(set.nearest(&8), Some(&9));
+        assert_eq!(set.nearest(&100), Some(&9));
+        assert_eq!(BTreeSet::<i32>::new().nearest(&0), None);
+
+        let mp = BTreeMap::from([(10u64, 1), (20, 2)]);
+        assert_eq!(mp.nearest(&16), Some((&20, &2)));
+        assert_eq!(mp.nearest(&15), Some((&10, &1)));
+    }
+
+    #[test]
+    fn test_map_mut() {
+        let mut mp = BTreeMap::from([(2, 10), (4, 20), (8, 30)]);
+        if let Some((_, v)) = mp.find_first_mut(&3) {
+            *v += 1;
+        }
+        if let Some((_, v)) = mp.find_last_mut(&3) {
+            *v += 2;
+        }
+        if let Some((_, v)) = mp.find_next_mut(&4) {
+            *v += 3;
+        }
+        if let Some((_, v)) = mp.find_prev_mut(&2) {
+            *v += 100;
+        }
+        assert_eq!(mp, BTreeMap::from([(2, 12), (4, 21), (8, 33)]));
+    }
 }
@@ -0,0 +1,232 @@
+//! 区間に値を割り当てるデータ構造です (いわゆる ODT, Chtholly Tree)
+//! 半開区間 [l, r) ごとに値を持ち、隣接する区間が同じ値の場合はマージします
+//!
+//! 「区間を塗る」クエリに使います。値が割り当てられていない位置があっても構いません。
+//!
+//! ## Example
+//! ```
+//! use kyopro_data_stractures::interval_map::IntervalMap;
+//! let mut mp = IntervalMap::new();
+//! mp.assign(0, 10, 'a');
+//! mp.assign(3, 5, 'b');
+//! mp.assign(5, 7, 'b');
+//! assert_eq!(mp.iter().collect::<Vec<_>>(), vec![(0, 3, &'a'), (3, 7, &'b'), (7, 10, &'a')]);
+//! assert_eq!(mp.get(4), Some(&'b'));
+//! assert_eq!(mp.range(2, 8).collect::<Vec<_>>(), vec![(2, 3, &'a'), (3, 7, &'b'), (7, 8, &'a')]);
+//!
+//! // 色ごとの長さを callback で管理する
+//! let mut len_b = 4;
+//! mp.assign_with(6, 12, 'c', |l, r, &v, added| {
+//!     if v == 'b' {
+//!         if added {
+//!             len_b += r - l;
+//!         } else {
+//!             len_b -= r - l;
+//!         }
+//!     }
+//! });
+//! assert_eq!(len_b, 3);
+//! ```
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug)]
+pub struct IntervalMap<K, V> {
+    /// l -> (r, v)
+    map: BTreeMap<K, (K, V)>,
+}
+
+impl<K: Copy + Ord, V: Clone + PartialEq> IntervalMap<K, V> {
+    pub fn new() -> Self {
+        IntervalMap { map: BTreeMap::new() }
+    }
+
+    /// x をまたぐ区間を x で分割します
+    fn split(&mut self, x: K) {
+        if let Some((&l, (r, v))) = self.map.range_mut(..x).next_back() {
+            if x < *r {
+                let (r, v) = (*r, v.clone());
+                self.map.get_mut(&l).unwrap().0 = x;
+                self.map.insert(x, (r, v));
+            }
+        }
+    }
+
+    /// [l, r) に含まれる区間を取り除きます
+    fn remove_in<F: FnMut(K, K, &V, bool)>(&mut self, l: K, r: K, on_change: &mut F) {
+        self.split(l);
+        self.split(r);
+        let keys: Vec<K> = self.map.range(l..r).map(|(&k, _)| k).collect();
+        for k in keys {
+            let (e, v) = self.map.remove(&k).unwrap();
+            on_change(k, e, &v, false);
+        }
+    }
+
+    /// l から始まる区間を、前後の同じ値の区間とマージします
+    fn merge_around(&mut self, l: K) {
+        let (r, v) = self.map[&l].clone();
+        if let Some((nr, nv)) = self.map.get(&r) {
+            if *nv == v {
+                let nr = *nr;
+                self.map.remove(&r);
+                self.map.get_mut(&l).unwrap().0 = nr;
+            }
+        }
+        if let Some((&pl, (pr, pv))) = self.map.range(..l).next_back() {
+            if *pr == l && *pv == v {
+                let (r, _) = self.map.remove(&l).unwrap();
+                self.map.get_mut(&pl).unwrap().0 = r;
+            }
+        }
+    }
+
+    /// [l, r) に値 v を割り当てます
+    /// O(log n) (ならし)
+    pub fn assign(&mut self, l: K, r: K, v: V) {
+        self.assign_with(l, r, v, |_, _, _, _| {});
+    }
+
+    /// [l, r) に値 v を割り当てます
+    /// 取り除かれた部分 `[a, b)` ごとに `on_change(a, b, &値, false)`、
+    /// 追加した区間について `on_change(l, r, &v, true)` を呼びます
+    /// (マージによる区間のつなぎ替えでは呼びません)
+    /// O(log n) (ならし)
+    pub fn assign_with<F: FnMut(K, K, &V, bool)>(&mut self, l: K, r: K, v: V, mut on_change: F) {
+        if l >= r {
+            return;
+        }
+        self.remove_in(l, r, &mut on_change);
+        on_change(l, r, &v, true);
+        self.map.insert(l, (r, v));
+        self.merge_around(l);
+    }
+
+    /// [l, r) の値の割り当てを解除します
+    /// O(log n) (ならし)
+    pub fn erase(&mut self, l: K, r: K) {
+        self.erase_with(l, r, |_, _, _, _| {});
+    }
+
+    /// [l, r) の値の割り当てを解除します
+    /// 取り除かれた部分 `[a, b)` ごとに `on_change(a, b, &値, false)` を呼びます
+    /// O(log n) (ならし)
+    pub fn erase_with<F: FnMut(K, K, &V, bool)>(&mut self, l: K, r: K, mut on_change: F) {
+        if l >= r {
+            return;
+        }
+        self.remove_in(l, r, &mut on_change);
+        // split で分けた両端を元に戻す
+        for x in [l, r] {
+            if let Some((&pl, _)) = self.map.range(..x).next_back() {
+                self.merge_around(pl);
+            }
+        }
+    }
+
+    /// x に割り当てられた値を返します
+    /// O(log n)
+    pub fn get(&self, x: K) -> Option<&V> {
+        match self.map.range(..=x).next_back() {
+            Some((_, (r, v))) if x < *r => Some(v),
+            _ => None,
+        }
+    }
+
+    /// x を含む区間を `(l, r, &値)` で返します
+    /// O(log n)
+    pub fn get_interval(&self, x: K) -> Option<(K, K, &V)> {
+        match self.map.range(..=x).next_back() {
+            Some((&l, (r, v))) if x < *r => Some((l, *r, v)),
+            _ => None,
+        }
+    }
+
+    /// [l, r) と重なる区間を [l, r) に切り詰めて `(l, r, &値)` で昇順に列挙します
+    pub fn range(&self, l: K, r: K) -> impl Iterator<Item = (K, K, &V)> + '_ {
+        let start = match self.get_interval(l) {
+            Some((s, _, _)) => s,
+            None => l,
+        };
+        let end = if l < r { r } else { start };
+        self.map
+            .range(start..end.max(start))
+            .map(move |(&a, (b, v))| (a.max(l), (*b).min(r), v))
+    }
+
+    /// 区間を `(l, r, &値)` で昇順に列挙します
+    pub fn iter(&self) -> impl Iterator<Item = (K, K, &V)> + '_ {
+        self.map.iter().map(|(&l, (r, v))| (l, *r, v))
+    }
+
+    /// 区間の個数を返します
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<K: Copy + Ord, V: Clone + PartialEq> Default for IntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalMap;
+
+    #[test]
+    fn test_naive() {
+        const N: usize = 30;
+        let mut mp = IntervalMap::new();
+        let mut naive: [Option<u8>; N] = [None; N];
+        // 色ごとの長さ
+        let mut cnt = [0usize; 3];
+        let mut x: u64 = 88172645463325252;
+        for _ in 0..1000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let l = (x % N as u64) as usize;
+            let r = ((x >> 20) % (N as u64 + 1)) as usize;
+            let c = ((x >> 40) % 4) as u8;
+            let mut on_change = |a: usize, b: usize, &v: &u8, added: bool| {
+                if added {
+                    cnt[v as usize] += b - a;
+                } else {
+                    cnt[v as usize] -= b - a;
+                }
+            };
+            if c == 3 {
+                mp.erase_with(l, r, &mut on_change);
+                if l < r {
+                    naive[l..r].fill(None);
+                }
+            } else {
+                mp.assign_with(l, r, c, &mut on_change);
+                if l < r {
+                    naive[l..r].fill(Some(c));
+                }
+            }
+
+            for (i, &c) in cnt.iter().enumerate() {
+                assert_eq!(c, naive.iter().filter(|&&v| v == Some(i as u8)).count());
+            }
+            for (i, v) in naive.iter().enumerate() {
+                assert_eq!(mp.get(i), v.as_ref());
+            }
+            // 隣接する区間は値が異なる
+            let pieces: Vec<(usize, usize, &u8)> = mp.iter().collect();
+            for w in pieces.windows(2) {
+                assert!(w[0].1 < w[1].0 || w[0].2 != w[1].2);
+            }
+            let (ql, qr) = (l.min(r), l.max(r));
+            let expected: Vec<(usize, u8)> = (ql..qr).filter_map(|i| naive[i].map(|v| (i, v))).collect();
+            let got: Vec<(usize, u8)> = mp.range(ql, qr).flat_map(|(a, b, &v)| (a..b).map(move |i| (i, v))).collect();
+            assert_eq!(got, expected);
+        }
+    }
+}
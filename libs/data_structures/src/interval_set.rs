@@ -0,0 +1,207 @@
+//! 区間の集合を管理するデータ構造です
+//! 半開区間 [l, r) の和集合を、重なり・隣接する区間をマージした状態で保持します
+//!
+//! `range_space::RangeSpace` と違い、重なる区間の追加や複数の区間にまたがる削除ができます
+//!
+//! ## Example
+//! ```
+//! use kyopro_data_stractures::interval_set::IntervalSet;
+//! let mut st = IntervalSet::new();
+//! st.insert(1, 5);
+//! st.insert(3, 8);
+//! st.insert(10, 12);
+//! assert_eq!(st.iter().collect::<Vec<_>>(), vec![(1, 8), (10, 12)]);
+//! st.remove(4, 11);
+//! assert_eq!(st.iter().collect::<Vec<_>>(), vec![(1, 4), (11, 12)]);
+//! assert_eq!(st.covered_len(), 4);
+//! assert!(st.contains(3));
+//! assert_eq!(st.mex(2), 4);
+//! ```
+use std::{
+    collections::BTreeMap,
+    ops::{Add, Sub},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct IntervalSet<T> {
+    /// l -> r
+    map: BTreeMap<T, T>,
+    /// 覆われている長さの合計
+    covered: T,
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Default,
+{
+    pub fn new() -> Self {
+        IntervalSet {
+            map: BTreeMap::new(),
+            covered: T::default(),
+        }
+    }
+
+    fn remove_interval(&mut self, l: T) -> T {
+        let r = self.map.remove(&l).unwrap();
+        self.covered = self.covered - (r - l);
+        r
+    }
+
+    fn insert_interval(&mut self, l: T, r: T) {
+        if l < r {
+            self.map.insert(l, r);
+            self.covered = self.covered + (r - l);
+        }
+    }
+
+    /// 区間 [l, r) を追加します
+    /// O(log n) (ならし)
+    pub fn insert(&mut self, l: T, r: T) {
+        if l >= r {
+            return;
+        }
+        let (mut l, mut r) = (l, r);
+        if let Some((&pl, &pr)) = self.map.range(..=l).next_back() {
+            if l <= pr {
+                l = pl;
+                r = r.max(pr);
+                self.remove_interval(pl);
+            }
+        }
+        // [l, r] から始まる区間はすべてマージされる
+        while let Some((&nl, _)) = self.map.range(l..=r).next() {
+            let nr = self.remove_interval(nl);
+            r = r.max(nr);
+        }
+        self.insert_interval(l, r);
+    }
+
+    /// 区間 [l, r) を削除します
+    /// O(log n) (ならし)
+    pub fn remove(&mut self, l: T, r: T) {
+        if l >= r {
+            return;
+        }
+        if let Some((&pl, &pr)) = self.map.range(..l).next_back() {
+            if l < pr {
+                self.remove_interval(pl);
+                self.insert_interval(pl, l);
+                self.insert_interval(r, pr);
+            }
+        }
+        while let Some((&nl, _)) = self.map.range(l..r).next() {
+            let nr = self.remove_interval(nl);
+            self.insert_interval(r, nr);
+        }
+    }
+
+    /// x を含む区間を返します
+    /// O(log n)
+    pub fn get(&self, x: T) -> Option<(T, T)> {
+        match self.map.range(..=x).next_back() {
+            Some((&l, &r)) if x < r => Some((l, r)),
+            _ => None,
+        }
+    }
+
+    /// x が含まれるかどうかを返します
+    /// O(log n)
+    pub fn contains(&self, x: T) -> bool {
+        self.get(x).is_some()
+    }
+
+    /// 区間 [l, r) 全体が含まれるかどうかを返します
+    /// O(log n)
+    pub fn contains_range(&self, l: T, r: T) -> bool {
+        if l >= r {
+            return true;
+        }
+        matches!(self.get(l), Some((_, cr)) if r <= cr)
+    }
+
+    /// x 以上で含まれていない最小の値を返します
+    /// O(log n)
+    pub fn mex(&self, x: T) -> T {
+        match self.get(x) {
+            Some((_, r)) => r,
+            None => x,
+        }
+    }
+
+    /// 覆われている長さの合計を返します
+    /// O(1)
+    pub fn covered_len(&self) -> T {
+        self.covered
+    }
+
+    /// 区間の個数を返します
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 区間を昇順に列挙します
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.map.iter().map(|(&l, &r)| (l, r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalSet;
+
+    #[test]
+    fn test_naive() {
+        const N: usize = 40;
+        let mut st = IntervalSet::new();
+        let mut naive = [false; N];
+        let mut x: u64 = 2463534242;
+        for _ in 0..500 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let l = (x % N as u64) as i32;
+            let r = ((x >> 20) % (N as u64 + 1)) as i32;
+            let is_remove = x % 3 == 0;
+            if is_remove {
+                st.remove(l, r);
+            } else {
+                st.insert(l, r);
+            }
+            if l < r {
+                naive[l as usize..r as usize].fill(!is_remove);
+            }
+
+            // 区間は昇順で、重なりも隣接もしない
+            let v: Vec<(i32, i32)> = st.iter().collect();
+            for w in v.windows(2) {
+                assert!(w[0].1 < w[1].0);
+            }
+            assert_eq!(st.covered_len(), naive.iter().filter(|&&b| b).count() as i32);
+            for i in 0..N {
+                assert_eq!(st.contains(i as i32), naive[i]);
+                let mex = (i..N).find(|&j| !naive[j]).unwrap_or(N);
+                assert_eq!(st.mex(i as i32), mex as i32);
+                assert!(st.contains_range(i as i32, mex as i32));
+            }
+        }
+    }
+
+    #[test]
+    fn test_adjacent_merge() {
+        let mut st = IntervalSet::new();
+        st.insert(1, 3);
+        st.insert(3, 5);
+        st.insert(7, 9);
+        assert_eq!(st.len(), 2);
+        st.insert(5, 7);
+        assert_eq!(st.iter().collect::<Vec<_>>(), vec![(1, 9)]);
+        assert!(!st.contains_range(0, 2));
+        st.remove(1, 9);
+        assert!(st.is_empty());
+        assert_eq!(st.covered_len(), 0);
+    }
+}
@@ -0,0 +1,191 @@
+//! 小さい方から k 個の要素とそれ以外を 2 つの `Multiset` で管理します
+//! スライド窓の中央値や、小さい方から k 個の和を求めるのに使います
+//!
+//! ## Example
+//! ```
+//! use kyopro_data_stractures::kth_window::KthWindow;
+//! let a = [5, 1, 4, 2, 3];
+//! // 幅 3 の窓の中央値
+//! let mut w = KthWindow::new(2);
+//! let mut medians = vec![];
+//! for i in 0..a.len() {
+//!     w.insert(a[i]);
+//!     if i >= 3 {
+//!         w.erase(a[i - 3]);
+//!     }
+//!     if i >= 2 {
+//!         medians.push(w.kth().unwrap());
+//!     }
+//! }
+//! assert_eq!(medians, vec![4, 2, 3]);
+//! // 小さい方から 2 個の和
+//! assert_eq!(w.sum_low(), 5);
+//! ```
+use crate::multiset::Multiset;
+use std::ops::{Add, Sub};
+
+#[derive(Clone, Debug)]
+pub struct KthWindow<T> {
+    k: usize,
+    /// 小さい方から k 個
+    low: Multiset<T>,
+    /// 残り
+    high: Multiset<T>,
+    sum_low: T,
+    sum_high: T,
+    /// k 番目の値 (low の最大値)
+    kth: Option<T>,
+}
+
+impl<T> KthWindow<T>
+where
+    T: Ord + Copy + Add<Output = T> + Sub<Output = T> + Default,
+{
+    pub fn new(k: usize) -> Self {
+        KthWindow {
+            k,
+            low: Multiset::new(),
+            high: Multiset::new(),
+            sum_low: T::default(),
+            sum_high: T::default(),
+            kth: None,
+        }
+    }
+
+    /// low の要素数が k になるように要素を移動します
+    fn balance(&mut self) {
+        while self.low.len() > self.k {
+            let v = self.low.pop_max().unwrap();
+            self.sum_low = self.sum_low - v;
+            self.sum_high = self.sum_high + v;
+            self.high.insert(v);
+        }
+        while self.low.len() < self.k {
+            let v = match self.high.pop_min() {
+                Some(v) => v,
+                None => break,
+            };
+            self.sum_high = self.sum_high - v;
+            self.sum_low = self.sum_low + v;
+            self.low.insert(v);
+        }
+        self.kth = if self.k > 0 && self.low.len() == self.k {
+            self.low.get_max()
+        } else {
+            None
+        };
+    }
+
+    /// 要素を追加します
+    /// O(log n)
+    pub fn insert(&mut self, x: T) {
+        match self.low.get_max() {
+            Some(m) if x < m => {
+                self.low.insert(x);
+                self.sum_low = self.sum_low + x;
+            }
+            _ => {
+                self.high.insert(x);
+                self.sum_high = self.sum_high + x;
+            }
+        }
+        self.balance();
+    }
+
+    /// 要素を 1 つ削除します
+    /// 要素が存在しなかった場合は false を返します
+    /// O(log n)
+    pub fn erase(&mut self, x: T) -> bool {
+        if self.high.erase(x) {
+            self.sum_high = self.sum_high - x;
+        } else if self.low.erase(x) {
+            self.sum_low = self.sum_low - x;
+        } else {
+            return false;
+        }
+        self.balance();
+        true
+    }
+
+    /// k を変更します
+    /// O(|k - 変更前の k| log n)
+    pub fn set_k(&mut self, k: usize) {
+        self.k = k;
+        self.balance();
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// 登録要素数を返します
+    pub fn len(&self) -> usize {
+        self.low.len() + self.high.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 小さい方から k 番目 (1-indexed) の値を返します
+    /// 要素数が k 未満の場合は None です
+    /// O(1)
+    pub fn kth(&self) -> Option<T> {
+        self.kth
+    }
+
+    /// 小さい方から k 個の和を返します
+    /// 要素数が k 未満の場合はすべての要素の和です
+    /// O(1)
+    pub fn sum_low(&self) -> T {
+        self.sum_low
+    }
+
+    /// 小さい方から k 個を除いた要素の和を返します
+    /// O(1)
+    pub fn sum_high(&self) -> T {
+        self.sum_high
+    }
+
+    /// すべての要素の和を返します
+    /// O(1)
+    pub fn sum(&self) -> T {
+        self.sum_low + self.sum_high
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KthWindow;
+
+    #[test]
+    fn test_naive() {
+        let mut w = KthWindow::new(3);
+        let mut v: Vec<i64> = vec![];
+        let mut x: u64 = 88172645463325252;
+        for _ in 0..1000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let a = (x % 20) as i64 - 5;
+            match x % 5 {
+                0 | 1 => {
+                    w.insert(a);
+                    v.push(a);
+                }
+                2 | 3 => {
+                    let expected = v.iter().position(|&b| b == a).map(|i| v.swap_remove(i)).is_some();
+                    assert_eq!(w.erase(a), expected);
+                }
+                _ => w.set_k((x % 7) as usize),
+            }
+            v.sort();
+            let k = w.k();
+            assert_eq!(w.len(), v.len());
+            assert_eq!(w.kth(), if k > 0 { v.get(k - 1).copied() } else { None });
+            assert_eq!(w.sum_low(), v.iter().take(k).sum::<i64>());
+            assert_eq!(w.sum_high(), v.iter().skip(k).sum::<i64>());
+            assert_eq!(w.sum(), v.iter().sum::<i64>());
+        }
+    }
+}
@@ -2,3 +2,9 @@ pub mod multiset;
 pub mod btree_neighbors;
 pub mod range_space;
 pub mod range_freq;
+pub mod binary_trie;
+pub mod wavelet_matrix;
+pub mod ordered_multiset;
+pub mod kth_window;
+pub mod interval_set;
+pub mod interval_map;
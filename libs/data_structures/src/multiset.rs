@@ -1,7 +1,10 @@
 //! multiset です
 //! 多重集合を管理します
 //!
-use std::collections::BTreeMap;
+//! `nth`, `count_less_than` は値の種類数に比例した時間がかかります。
+//! これらを O(log n) で行う場合は `ordered_multiset::OrderedMultiset` を使います。
+//!
+use std::{collections::BTreeMap, iter::FromIterator};
 
 use crate::btree_neighbors::MapNeighbors;
 
@@ -36,35 +39,84 @@ where
         *self.map.entry(val).or_insert(0) += 1;
     }
 
-    /// 要素を削除します
-    pub fn erase(&mut self, val: T) {
-        // HACK: 要素が存在しない場合はエラーにしたほうが良いですか?
-        if self.map.contains_key(&val) {
-            self.map.entry(val).and_modify(|v| *v -= 1);
-            if self.map[&val] == 0 {
-                self.map.remove(&val);
+    /// 要素を 1 つ削除します
+    /// 要素が存在しなかった場合は false を返します
+    pub fn erase(&mut self, val: T) -> bool {
+        match self.map.get_mut(&val) {
+            Some(c) => {
+                *c -= 1;
+                if *c == 0 {
+                    self.map.remove(&val);
+                }
+                self.size -= 1;
+                true
             }
-            self.size -= 1;
+            None => false,
         }
     }
 
+    /// 要素 val をすべて削除し、削除した個数を返します
+    pub fn erase_all(&mut self, val: T) -> usize {
+        let c = self.map.remove(&val).unwrap_or(0);
+        self.size -= c;
+        c
+    }
+
+    /// 要素 val の個数を返します
+    pub fn count(&self, val: &T) -> usize {
+        self.map.get(val).copied().unwrap_or(0)
+    }
+
     /// 最小値を返します
+    /// O(log n)
     pub fn get_min(&self) -> Option<T> {
-        if let Some((&k, _)) = self.map.iter().nth(0) {
-            return Some(k);
-        } else {
-            return None;
-        }
+        self.map.first_key_value().map(|(&k, _)| k)
     }
 
     /// 最大値を返します
+    /// O(log n)
     pub fn get_max(&self) -> Option<T> {
-        if let Some((&k, _)) = self.map.iter().last() {
-            return Some(k);
-        } else {
-            return None;
+        self.map.last_key_value().map(|(&k, _)| k)
+    }
+
+    /// 最小値を 1 つ削除して返します
+    pub fn pop_min(&mut self) -> Option<T> {
+        let k = self.get_min()?;
+        self.erase(k);
+        Some(k)
+    }
+
+    /// 最大値を 1 つ削除して返します
+    pub fn pop_max(&mut self) -> Option<T> {
+        let k = self.get_max()?;
+        self.erase(k);
+        Some(k)
+    }
+
+    /// 昇順で n 番目 (0-indexed) の値を返します
+    /// O(ユニークな要素数)
+    pub fn nth(&self, n: usize) -> Option<T> {
+        let mut n = n;
+        for (&k, &c) in &self.map {
+            if n < c {
+                return Some(k);
+            }
+            n -= c;
         }
+        None
     }
+
+    /// val 未満の要素数を返します
+    /// O(ユニークな要素数)
+    pub fn count_less_than(&self, val: &T) -> usize {
+        self.map.range(..val).map(|(_, &c)| c).sum()
+    }
+
+    /// 要素を重複を含めて昇順に列挙します
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.map.iter().flat_map(|(&k, &c)| std::iter::repeat(k).take(c))
+    }
+
     /// 要素 val が存在するかどうかを返します
     pub fn contains(&self, val: &T) -> bool {
         self.map.contains_key(val)
@@ -72,13 +124,7 @@ where
 
     /// 集合を vector で返します
     pub fn to_vec(&self) -> Vec<T> {
-        let mut res = vec![];
-        for (&k, &v) in self.map.iter() {
-            for _ in 0..v {
-                res.push(k);
-            }
-        }
-        return res;
+        self.iter().collect()
     }
     /// val 以上の最小の値を返します
     pub fn find_first(&self, val: &T) -> Option<T> {
@@ -102,12 +148,31 @@ where
     pub fn len(&self) -> usize {
         self.size
     }
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
     /// ユニークな要素数を返します
     pub fn unique_len(&self) -> usize {
         self.map.len()
     }
 }
 
+impl<T: Ord + Copy> Default for Multiset<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<T> for Multiset<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut st = Multiset::new();
+        for v in iter {
+            st.insert(v);
+        }
+        st
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Multiset;
@@ -175,4 +240,38 @@ mod tests {
         assert_eq!(st.find_last(&2), Some(2));
         assert_eq!(st.find_last(&1), None);
     }
+
+    #[test]
+    fn test_counted_operations() {
+        let mut st: Multiset<i32> = [5, 1, 3, 3, 5, 5].into_iter().collect();
+        assert_eq!(st.count(&5), 3);
+        assert_eq!(st.count(&4), 0);
+        assert!(!st.erase(4));
+        assert!(st.erase(3));
+        assert_eq!(st.erase_all(5), 3);
+        assert_eq!(st.erase_all(5), 0);
+        assert_eq!(st.to_vec(), vec![1, 3]);
+        assert_eq!(st.len(), 2);
+    }
+
+    #[test]
+    fn test_order_statistics() {
+        let mut st: Multiset<i32> = [4, 2, 2, 8, 6, 6, 6].into_iter().collect();
+        let sorted = vec![2, 2, 4, 6, 6, 6, 8];
+        for (i, &v) in sorted.iter().enumerate() {
+            assert_eq!(st.nth(i), Some(v));
+        }
+        assert_eq!(st.nth(7), None);
+        for x in 0..10 {
+            assert_eq!(st.count_less_than(&x), sorted.iter().filter(|&&v| v < x).count());
+        }
+        assert_eq!(st.iter().collect::<Vec<_>>(), sorted);
+        assert_eq!(st.pop_min(), Some(2));
+        assert_eq!(st.pop_max(), Some(8));
+        assert_eq!(st.pop_max(), Some(6));
+        assert_eq!(st.len(), 4);
+        let mut empty = Multiset::<i32>::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.pop_min(), None);
+    }
 }
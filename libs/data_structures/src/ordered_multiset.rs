@@ -0,0 +1,398 @@
+//! 順序統計量を扱える multiset です
+//! 部分木のサイズを持たせた treap で管理し、`nth`, `count_less_than` を O(log n) で行います
+//!
+//! ## Example
+//! ```
+//! use kyopro_data_stractures::ordered_multiset::OrderedMultiset;
+//! let mut st: OrderedMultiset<i32> = [5, 1, 3, 3].into_iter().collect();
+//! assert_eq!(st.nth(2), Some(3));
+//! assert_eq!(st.count_less_than(&4), 3);
+//! st.erase(3);
+//! assert_eq!(st.to_vec(), vec![1, 3, 5]);
+//! ```
+use std::iter::FromIterator;
+
+/// 子が存在しないことを表す値
+const NONE: usize = !0;
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    key: T,
+    /// key の個数
+    cnt: usize,
+    /// 部分木に含まれる要素数 (重複を含む)
+    size: usize,
+    pri: u64,
+    l: usize,
+    r: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct OrderedMultiset<T> {
+    root: usize,
+    nodes: Vec<Node<T>>,
+    /// 再利用できるノード
+    free: Vec<usize>,
+    rng: u64,
+}
+
+impl<T: Ord + Copy> OrderedMultiset<T> {
+    pub fn new() -> Self {
+        OrderedMultiset {
+            root: NONE,
+            nodes: vec![],
+            free: vec![],
+            rng: 88172645463325252,
+        }
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == NONE {
+            0
+        } else {
+            self.nodes[t].size
+        }
+    }
+
+    fn update(&mut self, t: usize) {
+        let (l, r) = (self.nodes[t].l, self.nodes[t].r);
+        self.nodes[t].size = self.size(l) + self.size(r) + self.nodes[t].cnt;
+    }
+
+    fn new_node(&mut self, key: T, cnt: usize) -> usize {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        let node = Node {
+            key,
+            cnt,
+            size: cnt,
+            pri: self.rng,
+            l: NONE,
+            r: NONE,
+        };
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i] = node;
+                i
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// t を (key < x, key >= x) に分割します
+    /// `inclusive` の場合は (key <= x, key > x) に分割します
+    fn split(&mut self, t: usize, x: &T, inclusive: bool) -> (usize, usize) {
+        if t == NONE {
+            return (NONE, NONE);
+        }
+        let go_left = if inclusive { *x < self.nodes[t].key } else { *x <= self.nodes[t].key };
+        if go_left {
+            let (a, b) = self.split(self.nodes[t].l, x, inclusive);
+            self.nodes[t].l = b;
+            self.update(t);
+            (a, t)
+        } else {
+            let (a, b) = self.split(self.nodes[t].r, x, inclusive);
+            self.nodes[t].r = a;
+            self.update(t);
+            (t, b)
+        }
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NONE {
+            return b;
+        }
+        if b == NONE {
+            return a;
+        }
+        if self.nodes[a].pri > self.nodes[b].pri {
+            let r = self.merge(self.nodes[a].r, b);
+            self.nodes[a].r = r;
+            self.update(a);
+            a
+        } else {
+            let l = self.merge(a, self.nodes[b].l);
+            self.nodes[b].l = l;
+            self.update(b);
+            b
+        }
+    }
+
+    /// key = val のノードの個数を f で書き換えます
+    /// 戻り値は書き換え前の個数です
+    fn modify<F: FnOnce(usize) -> usize>(&mut self, val: T, f: F) -> usize {
+        let (a, b) = self.split(self.root, &val, false);
+        let (m, c) = self.split(b, &val, true);
+        let old = if m == NONE { 0 } else { self.nodes[m].cnt };
+        let new = f(old);
+        let m = if new == 0 {
+            if m != NONE {
+                self.free.push(m);
+            }
+            NONE
+        } else if m == NONE {
+            self.new_node(val, new)
+        } else {
+            self.nodes[m].cnt = new;
+            self.update(m);
+            m
+        };
+        let am = self.merge(a, m);
+        self.root = self.merge(am, c);
+        old
+    }
+
+    /// 要素を追加します
+    /// O(log n)
+    pub fn insert(&mut self, val: T) {
+        self.modify(val, |c| c + 1);
+    }
+
+    /// 要素を 1 つ削除します
+    /// 要素が存在しなかった場合は false を返します
+    /// O(log n)
+    pub fn erase(&mut self, val: T) -> bool {
+        self.modify(val, |c| c.saturating_sub(1)) > 0
+    }
+
+    /// 要素 val をすべて削除し、削除した個数を返します
+    /// O(log n)
+    pub fn erase_all(&mut self, val: T) -> usize {
+        self.modify(val, |_| 0)
+    }
+
+    /// 要素 val の個数を返します
+    /// O(log n)
+    pub fn count(&self, val: &T) -> usize {
+        let mut t = self.root;
+        while t != NONE {
+            let node = &self.nodes[t];
+            if *val < node.key {
+                t = node.l;
+            } else if node.key < *val {
+                t = node.r;
+            } else {
+                return node.cnt;
+            }
+        }
+        0
+    }
+
+    /// 要素 val が存在するかどうかを返します
+    pub fn contains(&self, val: &T) -> bool {
+        self.count(val) > 0
+    }
+
+    /// 登録要素数を返します
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == NONE
+    }
+
+    /// 昇順で n 番目 (0-indexed) の値を返します
+    /// O(log n)
+    pub fn nth(&self, n: usize) -> Option<T> {
+        if n >= self.len() {
+            return None;
+        }
+        let mut n = n;
+        let mut t = self.root;
+        loop {
+            let node = &self.nodes[t];
+            let ls = self.size(node.l);
+            if n < ls {
+                t = node.l;
+            } else if n < ls + node.cnt {
+                return Some(node.key);
+            } else {
+                n -= ls + node.cnt;
+                t = node.r;
+            }
+        }
+    }
+
+    /// val 未満の要素数を返します
+    /// O(log n)
+    pub fn count_less_than(&self, val: &T) -> usize {
+        let mut res = 0;
+        let mut t = self.root;
+        while t != NONE {
+            let node = &self.nodes[t];
+            if node.key < *val {
+                res += self.size(node.l) + node.cnt;
+                t = node.r;
+            } else {
+                t = node.l;
+            }
+        }
+        res
+    }
+
+    /// 最小値を返します
+    pub fn get_min(&self) -> Option<T> {
+        self.nth(0)
+    }
+
+    /// 最大値を返します
+    pub fn get_max(&self) -> Option<T> {
+        self.len().checked_sub(1).and_then(|k| self.nth(k))
+    }
+
+    /// 最小値を 1 つ削除して返します
+    pub fn pop_min(&mut self) -> Option<T> {
+        let k = self.get_min()?;
+        self.erase(k);
+        Some(k)
+    }
+
+    /// 最大値を 1 つ削除して返します
+    pub fn pop_max(&mut self) -> Option<T> {
+        let k = self.get_max()?;
+        self.erase(k);
+        Some(k)
+    }
+
+    /// val 以上の最小の値を返します
+    pub fn find_first(&self, val: &T) -> Option<T> {
+        self.nth(self.count_less_than(val))
+    }
+
+    /// val 以下の最大の値を返します
+    pub fn find_last(&self, val: &T) -> Option<T> {
+        let mut res = None;
+        let mut t = self.root;
+        while t != NONE {
+            let node = &self.nodes[t];
+            if node.key <= *val {
+                res = Some(node.key);
+                t = node.r;
+            } else {
+                t = node.l;
+            }
+        }
+        res
+    }
+
+    /// 要素を重複を含めて昇順に列挙します
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut it = Iter {
+            st: self,
+            stack: vec![],
+            rest: 0,
+        };
+        it.push_left(self.root);
+        it
+    }
+
+    /// 集合を vector で返します
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+/// `OrderedMultiset::iter` の戻り値です
+pub struct Iter<'a, T> {
+    st: &'a OrderedMultiset<T>,
+    stack: Vec<usize>,
+    /// stack の先頭のノードの残りの出力回数
+    rest: usize,
+}
+
+impl<T> Iter<'_, T> {
+    fn push_left(&mut self, mut t: usize) {
+        while t != NONE {
+            self.stack.push(t);
+            t = self.st.nodes[t].l;
+        }
+        if let Some(&top) = self.stack.last() {
+            self.rest = self.st.nodes[top].cnt;
+        }
+    }
+}
+
+impl<T: Copy> Iterator for Iter<'_, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        let &t = self.stack.last()?;
+        let key = self.st.nodes[t].key;
+        self.rest -= 1;
+        if self.rest == 0 {
+            self.stack.pop();
+            self.push_left(self.st.nodes[t].r);
+            if let Some(&top) = self.stack.last() {
+                self.rest = self.st.nodes[top].cnt;
+            }
+        }
+        Some(key)
+    }
+}
+
+impl<T: Ord + Copy> Default for OrderedMultiset<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<T> for OrderedMultiset<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut st = OrderedMultiset::new();
+        for v in iter {
+            st.insert(v);
+        }
+        st
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrderedMultiset;
+    use crate::multiset::Multiset;
+
+    #[test]
+    fn test_same_as_multiset() {
+        let mut st = OrderedMultiset::new();
+        let mut naive = Multiset::new();
+        let mut x: u64 = 2463534242;
+        for _ in 0..2000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let v = (x % 30) as i32;
+            match x % 7 {
+                0..=2 => {
+                    st.insert(v);
+                    naive.insert(v);
+                }
+                3 | 4 => assert_eq!(st.erase(v), naive.erase(v)),
+                5 => assert_eq!(st.erase_all(v), naive.erase_all(v)),
+                _ => assert_eq!(st.pop_min(), naive.pop_min()),
+            }
+            assert_eq!(st.len(), naive.len());
+            assert_eq!(st.count(&v), naive.count(&v));
+            assert_eq!(st.count_less_than(&v), naive.count_less_than(&v));
+            assert_eq!(st.nth(v as usize), naive.nth(v as usize));
+            assert_eq!(st.find_first(&v), naive.find_first(&v));
+            assert_eq!(st.find_last(&v), naive.find_last(&v));
+            assert_eq!(st.get_max(), naive.get_max());
+        }
+        assert_eq!(st.to_vec(), naive.to_vec());
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut st = OrderedMultiset::<i32>::default();
+        assert!(st.is_empty());
+        assert_eq!(st.nth(0), None);
+        assert_eq!(st.pop_max(), None);
+        assert!(!st.erase(1));
+        assert_eq!(st.iter().count(), 0);
+    }
+}
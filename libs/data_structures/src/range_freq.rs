@@ -1,10 +1,16 @@
 use kyopro_utils::lower_bound;
+use std::ops::{Add, Bound, RangeBounds};
 
 /// 数列の区間 [l, r) において、 x 未満の値の要素数を取得する
 /// (蟻本 p.170)
+///
+/// 区間の k 番目の値など、より多くのクエリが必要な場合は `wavelet_matrix::WaveletMatrix` を使います
 pub struct RangeFreq<T> {
     n: usize,
+    len: usize,
     dat: Vec<Vec<T>>,
+    /// sums[k][i]: dat[k] の先頭 i 個の和 (`with_sums` で構築した場合のみ)
+    sums: Vec<Vec<T>>,
 }
 impl<T: Copy + Ord> RangeFreq<T> {
     /// RangeFreq の構築
@@ -14,6 +20,9 @@ impl<T: Copy + Ord> RangeFreq<T> {
     /// let rf = RangeFreq::new(&data);
     /// assert_eq!(rf.query(1, 5, 3), 1);
     /// assert_eq!(rf.query(1, 5, 5), 2);
+    /// assert_eq!(rf.query_range(1.., 5), 2);
+    /// assert_eq!(rf.count_in(0, 5, 2, 6), 2);
+    /// assert_eq!(rf.kth_smallest_range(1..=3, 0), 3);
     /// ```
     pub fn new(vec: &[T]) -> Self {
         // 木のサイズ
@@ -25,11 +34,17 @@ impl<T: Copy + Ord> RangeFreq<T> {
         vec.iter().enumerate().for_each(|(i, &v)| {
             dat[i + n - 1].push(v);
         });
-        for i in (0..=n - 2).rev() {
+        // 要素数が 1 以下の場合は葉 (= 根) だけなので、マージするノードはない
+        for i in (0..n - 1).rev() {
             // 2つの子をマージ
             dat[i] = Self::merge(&dat[i * 2 + 1], &dat[i * 2 + 2]);
         }
-        Self { n, dat }
+        Self {
+            n,
+            len: vec.len(),
+            dat,
+            sums: vec![],
+        }
     }
     fn merge(l: &[T], r: &[T]) -> Vec<T> {
         let mut vec: Vec<T> = Vec::with_capacity(l.len() + r.len());
@@ -46,18 +61,123 @@ impl<T: Copy + Ord> RangeFreq<T> {
         }
         vec
     }
-    fn _query(&self, a: usize, b: usize, x: T, k: usize, l: usize, r: usize) -> usize {
-        if b <= l || r <= a {
-            return 0;
-        }
-        if a <= l && r <= b {
-            return lower_bound(&self.dat[k], &x);
+    /// 区間を半開区間 [a, b) に変換します
+    fn bounds<R: RangeBounds<usize>>(&self, range: &R) -> (usize, usize) {
+        let a = match range.start_bound() {
+            Bound::Included(&a) => a,
+            Bound::Excluded(&a) => a + 1,
+            Bound::Unbounded => 0,
+        };
+        let b = match range.end_bound() {
+            Bound::Included(&b) => b + 1,
+            Bound::Excluded(&b) => b,
+            Bound::Unbounded => self.len,
+        };
+        (a, b)
+    }
+    /// [a, b) を覆うノードを下から辿り、番号 k を順に f で畳み込みます
+    fn fold<A>(&self, a: usize, b: usize, init: A, mut f: impl FnMut(A, usize) -> A) -> A {
+        assert!(a <= b && b <= self.len);
+        let mut acc = init;
+        // 1-indexed で下から辿る
+        let mut l = a + self.n;
+        let mut r = b + self.n;
+        while l < r {
+            if l & 1 == 1 {
+                acc = f(acc, l - 1);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                acc = f(acc, r - 1);
+            }
+            l >>= 1;
+            r >>= 1;
         }
-        return self._query(a, b, x, k * 2 + 1, l, (l + r) / 2) + self._query(a, b, x, k * 2 + 2, (l + r) / 2, r);
+        acc
     }
     /// [a, b) から x 未満の要素数を取得
+    /// O(log^2 n)
     pub fn query(&self, a: usize, b: usize, x: T) -> usize {
-        return self._query(a, b, x, 0, 0, self.n);
+        self.fold(a, b, 0, |acc, k| acc + lower_bound(&self.dat[k], &x))
+    }
+    /// `query` の区間を `RangeBounds` で指定する版
+    pub fn query_range<R: RangeBounds<usize>>(&self, range: R, x: T) -> usize {
+        let (a, b) = self.bounds(&range);
+        self.query(a, b, x)
+    }
+    /// [a, b) から lo 以上 hi 未満の要素数を取得
+    /// O(log^2 n)
+    pub fn count_in(&self, a: usize, b: usize, lo: T, hi: T) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        self.query(a, b, hi) - self.query(a, b, lo)
+    }
+    /// `count_in` の区間を `RangeBounds` で指定する版
+    pub fn count_in_range<R: RangeBounds<usize>>(&self, range: R, lo: T, hi: T) -> usize {
+        let (a, b) = self.bounds(&range);
+        self.count_in(a, b, lo, hi)
+    }
+    /// [a, b) の値を昇順に並べたときの k 番目 (0-indexed) の値を取得
+    /// O(log^3 n)
+    pub fn kth_smallest(&self, a: usize, b: usize, k: usize) -> T {
+        assert!(a <= b && k < b - a);
+        // 全体の値のうち、x 未満の要素数が k 以下となる最大の x が答え
+        let all = &self.dat[0];
+        let mut ok = 0;
+        let mut ng = all.len();
+        while ng - ok > 1 {
+            let m = (ok + ng) / 2;
+            if self.query(a, b, all[m]) <= k {
+                ok = m;
+            } else {
+                ng = m;
+            }
+        }
+        all[ok]
+    }
+    /// `kth_smallest` の区間を `RangeBounds` で指定する版
+    pub fn kth_smallest_range<R: RangeBounds<usize>>(&self, range: R, k: usize) -> T {
+        let (a, b) = self.bounds(&range);
+        self.kth_smallest(a, b, k)
+    }
+}
+impl<T: Copy + Ord + Add<Output = T> + Default> RangeFreq<T> {
+    /// `sum_less_than` が使えるように、各ノードの累積和も持って構築します
+    /// ```
+    /// use kyopro_data_stractures::range_freq::RangeFreq;
+    /// let rf = RangeFreq::with_sums(&[1, 5, 3, 6, 1]);
+    /// assert_eq!(rf.sum_less_than(1, 5, 6), 9);
+    /// assert_eq!(rf.sum_less_than_range(..3, 6), 9);
+    /// ```
+    pub fn with_sums(vec: &[T]) -> Self {
+        let mut rf = Self::new(vec);
+        rf.sums = rf
+            .dat
+            .iter()
+            .map(|d| {
+                let mut s = Vec::with_capacity(d.len() + 1);
+                s.push(T::default());
+                for &v in d {
+                    s.push(*s.last().unwrap() + v);
+                }
+                s
+            })
+            .collect();
+        rf
+    }
+    /// [a, b) の x 未満の要素の和を取得
+    /// `with_sums` で構築している必要があります
+    /// O(log^2 n)
+    pub fn sum_less_than(&self, a: usize, b: usize, x: T) -> T {
+        assert!(!self.sums.is_empty(), "build with RangeFreq::with_sums");
+        self.fold(a, b, T::default(), |acc, k| acc + self.sums[k][lower_bound(&self.dat[k], &x)])
+    }
+    /// `sum_less_than` の区間を `RangeBounds` で指定する版
+    pub fn sum_less_than_range<R: RangeBounds<usize>>(&self, range: R, x: T) -> T {
+        let (a, b) = self.bounds(&range);
+        self.sum_less_than(a, b, x)
     }
 }
 #[test]
@@ -83,3 +203,41 @@ fn test_range_freq() {
     assert_eq!(rf.query(8, 10, 10), 1);
     assert_eq!(rf.query(8, 10, 11), 2);
 }
+#[test]
+fn test_range_freq_naive() {
+    let data = vec![7i64, -2, 7, 3, 0, 12, 3, -2, 5];
+    let rf = RangeFreq::with_sums(&data);
+    let n = data.len();
+    for a in 0..=n {
+        for b in a..=n {
+            let s = &data[a..b];
+            for x in -3..=13 {
+                assert_eq!(rf.query(a, b, x), s.iter().filter(|&&v| v < x).count());
+                assert_eq!(rf.sum_less_than(a, b, x), s.iter().filter(|&&v| v < x).sum::<i64>());
+                assert_eq!(rf.count_in(a, b, x, 5), s.iter().filter(|&&v| x <= v && v < 5).count());
+            }
+            let mut sorted = s.to_vec();
+            sorted.sort();
+            for (k, &v) in sorted.iter().enumerate() {
+                assert_eq!(rf.kth_smallest(a, b, k), v);
+            }
+        }
+    }
+    assert_eq!(rf.query_range(.., 4), 5);
+    assert_eq!(rf.query_range(2..=4, 4), 2);
+    assert_eq!(rf.sum_less_than_range(6.., 100), 6);
+}
+#[test]
+fn test_range_freq_small() {
+    let rf = RangeFreq::<i32>::with_sums(&[]);
+    assert_eq!(rf.query_range(.., 5), 0);
+    assert_eq!(rf.count_in_range(.., 0, 10), 0);
+    assert_eq!(rf.sum_less_than_range(.., 5), 0);
+
+    let rf = RangeFreq::with_sums(&[7]);
+    assert_eq!(rf.query_range(.., 7), 0);
+    assert_eq!(rf.query_range(.., 8), 1);
+    assert_eq!(rf.query(0, 0, 8), 0);
+    assert_eq!(rf.kth_smallest_range(.., 0), 7);
+    assert_eq!(rf.sum_less_than_range(.., 8), 7);
+}
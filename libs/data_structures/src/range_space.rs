@@ -1,10 +1,36 @@
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, fmt};
+
+/// `RangeSpace` の操作が失敗した理由
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeSpaceError {
+    /// r が inf を超えている
+    ExceedsInf,
+    /// 保有する区間と重複している
+    Overlap,
+    /// 消費する区間を含む保有区間がない
+    NotFound,
+}
+
+impl fmt::Display for RangeSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            RangeSpaceError::ExceedsInf => "r が inf を超えています",
+            RangeSpaceError::Overlap => "区間が重複しています",
+            RangeSpaceError::NotFound => "not found space",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for RangeSpaceError {}
 
 /// 区間を管理するデータ構造
 /// TODO: 名称要検討 (というか、すでに名前ついててもおかしくないと思う)
 ///
 /// 以下、追加された区間を"保有"、保有する区間を削除することを"消費"と呼びます
 ///
+/// 重なる区間の追加や、複数の区間にまたがる削除が必要な場合は `interval_set::IntervalSet` を使います
+///
 /// ## Example
 /// ```
 /// use kyopro_data_stractures::range_space::RangeSpace;
@@ -73,9 +99,9 @@ where
     }
 
     /// 区間 [l, r) を追加します
-    pub fn add_range(&mut self, l: T, r: T) -> Result<(), String> {
+    pub fn add_range(&mut self, l: T, r: T) -> Result<(), RangeSpaceError> {
         if r > self.inf {
-            return Err("r が inf を超えています".to_string());
+            return Err(RangeSpaceError::ExceedsInf);
         }
         let mut after_range = self.st.range((
             std::ops::Bound::Included((l, self.inf)),
@@ -84,7 +110,7 @@ where
         if let Some(&(nxt_r, nxt_l)) = after_range.next() {
             // 区間が重複していない
             if r > nxt_l {
-                return Err("区間が重複しています".to_string());
+                return Err(RangeSpaceError::Overlap);
             }
 
             if r == nxt_l {
@@ -100,7 +126,7 @@ where
         ));
         if let Some(&(pre_r, pre_l)) = before_range.next_back() {
             if pre_r > l {
-                return Err("区間が重複しています".to_string());
+                return Err(RangeSpaceError::Overlap);
             }
             if pre_r == l {
                 // merge
@@ -109,11 +135,11 @@ where
             }
         }
         self.st.insert((r, l));
-        return Ok(());
+        Ok(())
     }
 
     /// 区間 [l, r) を消費します
-    pub fn use_range(&mut self, l: T, r: T) -> Result<(), String> {
+    pub fn use_range(&mut self, l: T, r: T) -> Result<(), RangeSpaceError> {
         let mut range = self.st.range((
             std::ops::Bound::Included((l, self.inf)),
             std::ops::Bound::Unbounded,
@@ -131,13 +157,13 @@ where
                 return Ok(());
             }
         }
-        Err("not found space".to_string())
+        Err(RangeSpaceError::NotFound)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::RangeSpace;
+    use super::{RangeSpace, RangeSpaceError};
 
     #[test]
     fn test_add_space() {
@@ -175,6 +201,9 @@ mod tests {
 
         assert!(rs.add_range(9, 10).is_ok());
         assert!(rs.add_range(20, 21).is_ok());
+
+        assert_eq!(rs.add_range(15, 16), Err(RangeSpaceError::Overlap));
+        assert_eq!(rs.add_range(9000, 10001), Err(RangeSpaceError::ExceedsInf));
     }
 
     #[test]
@@ -196,7 +225,7 @@ mod tests {
         assert_eq!(rs.st.len(), 2);
 
         // 使用済み区間を指定した場合は err
-        assert!(rs.use_range(1, 2).is_err());
+        assert_eq!(rs.use_range(1, 2), Err(RangeSpaceError::NotFound));
     }
     #[test]
     fn test_first_point() {
@@ -0,0 +1,356 @@
+//! Wavelet Matrix です
+//! 静的な数列に対して、区間の k 番目の値や値の範囲に含まれる要素数などを求めます
+//!
+//! 値は内部で座標圧縮するので、値の大きさによらず 1 クエリ O(log σ) (σ は値の種類数) です。
+//! 「区間 [l, r) の x 未満の要素数」だけが必要な場合は `range_freq::RangeFreq` でも構いません。
+//!
+//! ## Example
+//! ```
+//! use kyopro_data_stractures::wavelet_matrix::WaveletMatrix;
+//! let wm = WaveletMatrix::new(&[5, 1, 4, 1, 5, 9, 2, 6]);
+//! // [1, 6) = [1, 4, 1, 5, 9]
+//! assert_eq!(wm.kth_smallest(1, 6, 2), 4);
+//! assert_eq!(wm.range_freq(1, 6, 2, 9), 2);
+//! assert_eq!(wm.prev_value(1, 6, 4), Some(1));
+//! assert_eq!(wm.next_value(1, 6, 6), Some(9));
+//! assert_eq!(wm.rank(1, 4), 2);
+//! assert_eq!(wm.select(5, 1), Some(4));
+//! ```
+use kyopro_utils::lower_bound;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// rank を O(1) で求められる bit 列
+#[derive(Clone, Debug)]
+struct BitVector {
+    blocks: Vec<u64>,
+    /// cum[i]: blocks[..i] に含まれる 1 の個数
+    cum: Vec<usize>,
+}
+
+impl BitVector {
+    fn new(bits: &[bool]) -> Self {
+        let mut blocks = vec![0u64; bits.len() / 64 + 1];
+        for (i, &b) in bits.iter().enumerate() {
+            if b {
+                blocks[i >> 6] |= 1 << (i & 63);
+            }
+        }
+        let mut cum = vec![0; blocks.len() + 1];
+        for (i, b) in blocks.iter().enumerate() {
+            cum[i + 1] = cum[i] + b.count_ones() as usize;
+        }
+        BitVector { blocks, cum }
+    }
+
+    fn access(&self, i: usize) -> bool {
+        self.blocks[i >> 6] >> (i & 63) & 1 == 1
+    }
+
+    /// [0, i) に含まれる 1 の個数
+    fn rank1(&self, i: usize) -> usize {
+        let mask = (1u64 << (i & 63)) - 1;
+        self.cum[i >> 6] + (self.blocks[i >> 6] & mask).count_ones() as usize
+    }
+
+    /// [0, i) に含まれる 0 の個数
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WaveletMatrix<T> {
+    n: usize,
+    /// 圧縮前の値 (昇順, 重複なし)
+    vals: Vec<T>,
+    bits: usize,
+    /// 上位 bit から順に並べた bit 列
+    mat: Vec<BitVector>,
+    /// 各段の 0 の個数
+    zeros: Vec<usize>,
+}
+
+impl<T: Copy + Ord> WaveletMatrix<T> {
+    /// O(n log σ)
+    pub fn new(a: &[T]) -> Self {
+        let n = a.len();
+        let mut vals = a.to_vec();
+        vals.sort();
+        vals.dedup();
+        let mut cur: Vec<usize> = a.iter().map(|x| lower_bound(&vals, x)).collect();
+        let mut bits = 0;
+        while (1 << bits) < vals.len() {
+            bits += 1;
+        }
+        let mut mat = Vec::with_capacity(bits);
+        let mut zeros = Vec::with_capacity(bits);
+        for d in (0..bits).rev() {
+            let b: Vec<bool> = cur.iter().map(|&v| v >> d & 1 == 1).collect();
+            mat.push(BitVector::new(&b));
+            // 0 の要素を前に、1 の要素を後ろに安定に並べ替える
+            let (mut lo, hi): (Vec<usize>, Vec<usize>) = cur.iter().partition(|&&v| v >> d & 1 == 0);
+            zeros.push(lo.len());
+            lo.extend(hi);
+            cur = lo;
+        }
+        WaveletMatrix {
+            n,
+            vals,
+            bits,
+            mat,
+            zeros,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// 圧縮後の値を返します
+    fn compressed(&self, x: &T) -> Option<usize> {
+        let c = lower_bound(&self.vals, x);
+        if c < self.vals.len() && self.vals[c] == *x {
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    /// 1段下の区間に移動します
+    fn descend(&self, d: usize, l: usize, r: usize, bit: bool) -> (usize, usize) {
+        let bv = &self.mat[d];
+        if bit {
+            (self.zeros[d] + bv.rank1(l), self.zeros[d] + bv.rank1(r))
+        } else {
+            (bv.rank0(l), bv.rank0(r))
+        }
+    }
+
+    /// i 番目の値を返します
+    /// O(log σ)
+    pub fn access(&self, i: usize) -> T {
+        assert!(i < self.n);
+        let mut i = i;
+        let mut res = 0;
+        for d in 0..self.bits {
+            let bit = self.mat[d].access(i);
+            res = res << 1 | bit as usize;
+            i = if bit {
+                self.zeros[d] + self.mat[d].rank1(i)
+            } else {
+                self.mat[d].rank0(i)
+            };
+        }
+        self.vals[res]
+    }
+
+    /// [0, r) に含まれる x の個数を返します
+    /// O(log σ)
+    pub fn rank(&self, x: T, r: usize) -> usize {
+        assert!(r <= self.n);
+        let c = match self.compressed(&x) {
+            Some(c) => c,
+            None => return 0,
+        };
+        let (mut l, mut r) = (0, r);
+        for d in 0..self.bits {
+            (l, r) = self.descend(d, l, r, c >> (self.bits - 1 - d) & 1 == 1);
+        }
+        r - l
+    }
+
+    /// k 番目 (0-indexed) に出現する x の位置を返します
+    /// O(log n log σ)
+    pub fn select(&self, x: T, k: usize) -> Option<usize> {
+        if self.rank(x, self.n) <= k {
+            return None;
+        }
+        // rank(x, p) > k となる最小の p
+        let (mut ng, mut ok) = (0, self.n);
+        while ok - ng > 1 {
+            let m = (ok + ng) / 2;
+            if self.rank(x, m) > k {
+                ok = m;
+            } else {
+                ng = m;
+            }
+        }
+        Some(ok - 1)
+    }
+
+    /// [l, r) の値を昇順に並べたときの k 番目 (0-indexed) の値を返します
+    /// O(log σ)
+    pub fn kth_smallest(&self, l: usize, r: usize, k: usize) -> T {
+        assert!(l <= r && r <= self.n);
+        assert!(k < r - l);
+        let (mut l, mut r, mut k) = (l, r, k);
+        let mut res = 0;
+        for d in 0..self.bits {
+            let z = self.mat[d].rank0(r) - self.mat[d].rank0(l);
+            let bit = k >= z;
+            if bit {
+                k -= z;
+            }
+            res = res << 1 | bit as usize;
+            (l, r) = self.descend(d, l, r, bit);
+        }
+        self.vals[res]
+    }
+
+    /// [l, r) の値を降順に並べたときの k 番目 (0-indexed) の値を返します
+    /// O(log σ)
+    pub fn kth_largest(&self, l: usize, r: usize, k: usize) -> T {
+        assert!(k < r - l);
+        self.kth_smallest(l, r, r - l - 1 - k)
+    }
+
+    /// [l, r) に含まれる x 未満の値の個数を返します
+    /// O(log σ)
+    pub fn count_less_than(&self, l: usize, r: usize, x: T) -> usize {
+        assert!(l <= r && r <= self.n);
+        let c = lower_bound(&self.vals, &x);
+        if c >= 1 << self.bits {
+            return r - l;
+        }
+        let (mut l, mut r) = (l, r);
+        let mut res = 0;
+        for d in 0..self.bits {
+            let bit = c >> (self.bits - 1 - d) & 1 == 1;
+            if bit {
+                res += self.mat[d].rank0(r) - self.mat[d].rank0(l);
+            }
+            (l, r) = self.descend(d, l, r, bit);
+        }
+        res
+    }
+
+    /// [l, r) に含まれる lo 以上 hi 未満の値の個数を返します
+    /// O(log σ)
+    pub fn range_freq(&self, l: usize, r: usize, lo: T, hi: T) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        self.count_less_than(l, r, hi) - self.count_less_than(l, r, lo)
+    }
+
+    /// [l, r) に含まれる x 未満の値のうち最大のものを返します
+    /// O(log σ)
+    pub fn prev_value(&self, l: usize, r: usize, x: T) -> Option<T> {
+        let cnt = self.count_less_than(l, r, x);
+        if cnt == 0 {
+            None
+        } else {
+            Some(self.kth_smallest(l, r, cnt - 1))
+        }
+    }
+
+    /// [l, r) に含まれる x 以上の値のうち最小のものを返します
+    /// O(log σ)
+    pub fn next_value(&self, l: usize, r: usize, x: T) -> Option<T> {
+        let cnt = self.count_less_than(l, r, x);
+        if cnt == r - l {
+            None
+        } else {
+            Some(self.kth_smallest(l, r, cnt))
+        }
+    }
+
+    /// [l, r) で出現回数の多い値を k 個まで、`(値, 出現回数)` の出現回数の降順で返します
+    /// 出現回数が同じ場合は値の小さい方を先に返します
+    /// O(k log σ log k)
+    pub fn top_k(&self, l: usize, r: usize, k: usize) -> Vec<(T, usize)> {
+        assert!(l <= r && r <= self.n);
+        let mut res = vec![];
+        // (区間の幅, Reverse(部分木の最小の値), 段, l, r)
+        let mut que = BinaryHeap::new();
+        if l < r {
+            que.push((r - l, Reverse(0), 0, l, r));
+        }
+        while let Some((w, Reverse(v), d, l, r)) = que.pop() {
+            if res.len() == k {
+                break;
+            }
+            if d == self.bits {
+                res.push((self.vals[v], w));
+                continue;
+            }
+            for bit in [false, true] {
+                let (nl, nr) = self.descend(d, l, r, bit);
+                if nl < nr {
+                    let nv = if bit { v | 1 << (self.bits - 1 - d) } else { v };
+                    que.push((nr - nl, Reverse(nv), d + 1, nl, nr));
+                }
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WaveletMatrix;
+
+    #[test]
+    fn test_naive() {
+        let a = vec![30i64, -5, 12, 30, 7, -5, 100, 12, 12, 0, 7, 30, -5];
+        let wm = WaveletMatrix::new(&a);
+        let n = a.len();
+        for (i, &x) in a.iter().enumerate() {
+            assert_eq!(wm.access(i), x);
+        }
+        for x in [-5, 0, 7, 12, 30, 100, 1, 200] {
+            for r in 0..=n {
+                assert_eq!(wm.rank(x, r), a[..r].iter().filter(|&&y| y == x).count());
+            }
+            let pos: Vec<usize> = (0..n).filter(|&i| a[i] == x).collect();
+            for k in 0..=pos.len() {
+                assert_eq!(wm.select(x, k), pos.get(k).copied());
+            }
+        }
+        for l in 0..=n {
+            for r in l..=n {
+                let mut s = a[l..r].to_vec();
+                s.sort();
+                for (k, &v) in s.iter().enumerate() {
+                    assert_eq!(wm.kth_smallest(l, r, k), v);
+                    assert_eq!(wm.kth_largest(l, r, s.len() - 1 - k), v);
+                }
+                for x in -6..=101 {
+                    assert_eq!(wm.count_less_than(l, r, x), s.iter().filter(|&&y| y < x).count());
+                    assert_eq!(wm.prev_value(l, r, x), s.iter().rev().find(|&&y| y < x).copied());
+                    assert_eq!(wm.next_value(l, r, x), s.iter().find(|&&y| y >= x).copied());
+                }
+                assert_eq!(wm.range_freq(l, r, 0, 30), s.iter().filter(|&&y| (0..30).contains(&y)).count());
+                assert_eq!(wm.range_freq(l, r, 30, 0), 0);
+
+                let mut freq: Vec<(i64, usize)> = vec![];
+                for &v in &s {
+                    match freq.last_mut() {
+                        Some((u, c)) if *u == v => *c += 1,
+                        _ => freq.push((v, 1)),
+                    }
+                }
+                freq.sort_by_key(|&(v, c)| (std::cmp::Reverse(c), v));
+                for k in 0..=freq.len() + 1 {
+                    assert_eq!(wm.top_k(l, r, k), freq[..k.min(freq.len())].to_vec());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_value() {
+        let wm = WaveletMatrix::new(&[7u32, 7, 7]);
+        assert_eq!(wm.kth_smallest(0, 3, 2), 7);
+        assert_eq!(wm.rank(7, 2), 2);
+        assert_eq!(wm.count_less_than(0, 3, 8), 3);
+        assert_eq!(wm.top_k(0, 3, 5), vec![(7, 3)]);
+        let empty = WaveletMatrix::<u32>::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.select(0, 0), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! 2次元 BIT
+//! `kyopro_accumlate2d::Accumulate2D` と同じ形のクエリに、値の更新をしながら答えます
+//!
+//! ## Example
+//! ```
+//! use kyopro_fenwick::fenwick2d::Fenwick2D;
+//! let mut bit = Fenwick2D::new(3, 3);
+//! for i in 0..3 {
+//!     for j in 0..3 {
+//!         bit.add(i, j, (i * 3 + j) as i32);
+//!     }
+//! }
+//! assert_eq!(bit.query(1, 3, 1, 3), 24);
+//! bit.add(2, 2, 10);
+//! assert_eq!(bit.query(1, 3, 1, 3), 34);
+//! ```
+use std::ops::{Add, AddAssign, Neg, Sub};
+
+#[derive(Debug, Clone)]
+pub struct Fenwick2D<T> {
+    h: usize,
+    w: usize,
+    // 1-indexed
+    tree: Vec<Vec<T>>,
+}
+
+impl<T> Fenwick2D<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T> + AddAssign,
+{
+    /// すべての要素が `T::default()` の h x w の BIT を作成します
+    pub fn new(h: usize, w: usize) -> Self {
+        Fenwick2D {
+            h,
+            w,
+            tree: vec![vec![T::default(); w + 1]; h + 1],
+        }
+    }
+
+    /// (r, c) に v を加算します
+    /// O(log H log W)
+    pub fn add(&mut self, r: usize, c: usize, v: T) {
+        assert!(r < self.h && c < self.w);
+        let mut i = r + 1;
+        while i <= self.h {
+            let mut j = c + 1;
+            while j <= self.w {
+                self.tree[i][j] += v;
+                j += j & j.wrapping_neg();
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// (r, c) の値を返します
+    /// O(log H log W)
+    pub fn get(&self, r: usize, c: usize) -> T {
+        self.query(r, r + 1, c, c + 1)
+    }
+
+    /// [0, b) x [0, r) の和を求めます
+    /// O(log H log W)
+    pub fn prefix_sum(&self, b: usize, r: usize) -> T {
+        assert!(b <= self.h && r <= self.w);
+        let mut res = T::default();
+        let mut i = b;
+        while i > 0 {
+            let mut j = r;
+            while j > 0 {
+                res += self.tree[i][j];
+                j -= j & j.wrapping_neg();
+            }
+            i -= i & i.wrapping_neg();
+        }
+        res
+    }
+
+    /// 左上座標 (t, l) から 右下座標 (b, r) の範囲の和を求めます (右下は半開区間)
+    /// 範囲指定順注意 [t, b), [l, r) の順で指定する
+    /// O(log H log W)
+    pub fn query(&self, t: usize, b: usize, l: usize, r: usize) -> T {
+        assert!(t <= b && b <= self.h && l <= r && r <= self.w);
+        self.prefix_sum(b, r) + self.prefix_sum(t, l) - self.prefix_sum(b, l) - self.prefix_sum(t, r)
+    }
+}
+
+impl<T> Fenwick2D<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T> + AddAssign + Neg<Output = T>,
+{
+    /// (r, c) の値を v にします
+    /// 値が減ることがあるので、符号付きの型でのみ使えます
+    /// O(log H log W)
+    pub fn set(&mut self, r: usize, c: usize, v: T) {
+        let cur = self.get(r, c);
+        self.add(r, c, v + -cur);
+    }
+}
+
+#[test]
+fn test_query() {
+    let (h, w) = (4, 5);
+    let mut a = vec![vec![0i64; w]; h];
+    let mut bit = Fenwick2D::new(h, w);
+    for (r, c, v) in [(0, 0, 3), (1, 4, -2), (3, 2, 7), (2, 2, 5), (0, 0, 1)] {
+        bit.add(r, c, v);
+        a[r][c] += v;
+    }
+    bit.set(3, 2, 4);
+    a[3][2] = 4;
+    for t in 0..=h {
+        for b in t..=h {
+            for l in 0..=w {
+                for r in l..=w {
+                    let naive: i64 = a[t..b].iter().map(|row| row[l..r].iter().sum::<i64>()).sum();
+                    assert_eq!(bit.query(t, b, l, r), naive);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Binary Indexed Tree (Fenwick tree)
+//!
+//! - `Fenwick`: 一点加算・区間和
+//! - `range_add::RangeAddFenwick`: 区間加算・区間和
+//! - `fenwick2d::Fenwick2D`: 2次元の一点加算・矩形和
+//!
+//! 区間はすべて半開区間 `[l, r)` で指定します
+//!
+//! ## Example
+//! ```
+//! use kyopro_fenwick::Fenwick;
+//! let mut bit = Fenwick::from(vec![3, 1, 4, 1, 5]);
+//! assert_eq!(bit.sum(1, 4), 6);
+//! bit.add(2, 10);
+//! assert_eq!(bit.prefix_sum(3), 18);
+//! // 累積和が 15 以上になる最初の位置
+//! assert_eq!(bit.lower_bound(15), 2);
+//! ```
+pub mod fenwick2d;
+pub mod range_add;
+
+use std::ops::{AddAssign, Neg, Sub};
+
+#[derive(Debug, Clone)]
+pub struct Fenwick<T> {
+    n: usize,
+    // 1-indexed
+    tree: Vec<T>,
+}
+
+impl<T> From<Vec<T>> for Fenwick<T>
+where
+    T: Copy + Default + AddAssign + Sub<Output = T>,
+{
+    /// O(n)
+    fn from(v: Vec<T>) -> Self {
+        let n = v.len();
+        let mut tree = vec![T::default(); n + 1];
+        tree[1..].copy_from_slice(&v);
+        for i in 1..=n {
+            let j = i + (i & i.wrapping_neg());
+            if j <= n {
+                let x = tree[i];
+                tree[j] += x;
+            }
+        }
+        Fenwick { n, tree }
+    }
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + AddAssign + Sub<Output = T>,
+{
+    /// 長さ `n` で、すべての要素が `T::default()` の BIT を作成します
+    pub fn new(n: usize) -> Self {
+        Fenwick {
+            n,
+            tree: vec![T::default(); n + 1],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// i 番目の要素に v を加算します
+    /// O(log n)
+    pub fn add(&mut self, i: usize, v: T) {
+        assert!(i < self.n);
+        let mut i = i + 1;
+        while i <= self.n {
+            self.tree[i] += v;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// i 番目の要素を返します
+    /// O(log n)
+    pub fn get(&self, i: usize) -> T {
+        self.sum(i, i + 1)
+    }
+
+    /// [0, r) の和を求めます
+    /// O(log n)
+    pub fn prefix_sum(&self, r: usize) -> T {
+        assert!(r <= self.n);
+        let mut res = T::default();
+        let mut r = r;
+        while r > 0 {
+            res += self.tree[r];
+            r -= r & r.wrapping_neg();
+        }
+        res
+    }
+
+    /// [l, r) の和を求めます
+    /// O(log n)
+    pub fn sum(&self, l: usize, r: usize) -> T {
+        assert!(l <= r);
+        self.prefix_sum(r) - self.prefix_sum(l)
+    }
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + AddAssign + Sub<Output = T> + Neg<Output = T>,
+{
+    /// i 番目の要素を v にします
+    /// 値が減ることがあるので、符号付きの型でのみ使えます
+    /// ```compile_fail
+    /// use kyopro_fenwick::Fenwick;
+    /// let mut bit = Fenwick::<u64>::new(3);
+    /// bit.set(0, 1);
+    /// ```
+    /// O(log n)
+    pub fn set(&mut self, i: usize, v: T) {
+        let mut d = -self.get(i);
+        d += v;
+        self.add(i, d);
+    }
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + AddAssign + Sub<Output = T> + PartialOrd,
+{
+    /// `prefix_sum(i + 1) >= w` となる最小の i を返します
+    /// 存在しない場合は n を返します
+    /// すべての要素が非負である必要があります
+    /// O(log n)
+    pub fn lower_bound(&self, w: T) -> usize {
+        let mut w = w;
+        if w <= T::default() {
+            return 0;
+        }
+        let mut pos = 0;
+        let mut step = if self.n == 0 { 0 } else { 1 << self.n.ilog2() };
+        while step > 0 {
+            if pos + step <= self.n && self.tree[pos + step] < w {
+                w = w - self.tree[pos + step];
+                pos += step;
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fenwick;
+
+    #[test]
+    fn test_sum() {
+        let mut v = vec![5i64, -3, 8, 0, 2, 7, -1];
+        let mut bit = Fenwick::from(v.clone());
+        for (i, x) in [(3, 4), (0, -2), (6, 10)] {
+            bit.add(i, x);
+            v[i] += x;
+        }
+        bit.set(2, 1);
+        v[2] = 1;
+        for l in 0..=v.len() {
+            for r in l..=v.len() {
+                assert_eq!(bit.sum(l, r), v[l..r].iter().sum::<i64>());
+            }
+        }
+        for (i, &x) in v.iter().enumerate() {
+            assert_eq!(bit.get(i), x);
+        }
+    }
+
+    #[test]
+    fn test_unsigned() {
+        let mut v = vec![4u64, 0, 7, 1, 3];
+        let mut bit = Fenwick::from(v.clone());
+        for (i, x) in [(1, 5), (4, 2), (0, 1)] {
+            bit.add(i, x);
+            v[i] += x;
+        }
+        for l in 0..=v.len() {
+            for r in l..=v.len() {
+                assert_eq!(bit.sum(l, r), v[l..r].iter().sum::<u64>());
+            }
+        }
+        for (i, &x) in v.iter().enumerate() {
+            assert_eq!(bit.get(i), x);
+        }
+    }
+
+    #[test]
+    fn test_lower_bound() {
+        let v = vec![1u64, 0, 3, 2, 0, 0, 4];
+        let bit = Fenwick::from(v.clone());
+        for w in 0..=12 {
+            let naive = (0..v.len())
+                .find(|&i| v[..=i].iter().sum::<u64>() >= w)
+                .unwrap_or(v.len());
+            assert_eq!(bit.lower_bound(w), naive);
+        }
+        assert_eq!(Fenwick::<u64>::new(0).lower_bound(1), 0);
+    }
+}
@@ -0,0 +1,107 @@
+//! 区間加算・区間和の BIT
+//! 2本の BIT を使い、`prefix_sum(r) = b0(r) + b1(r) * r` で求めます
+//!
+//! ## Example
+//! ```
+//! use kyopro_fenwick::range_add::RangeAddFenwick;
+//! let mut bit = RangeAddFenwick::<i64>::new(5);
+//! bit.add(1, 4, 2);
+//! bit.add(0, 2, 1);
+//! assert_eq!(bit.sum(0, 5), 8);
+//! assert_eq!(bit.get(1), 3);
+//! ```
+use crate::Fenwick;
+use std::{
+    fmt::Debug,
+    ops::{AddAssign, Mul, Neg, Sub},
+};
+
+/// 区間加算・区間和の BIT です
+/// 内部で負の値を持つので、`T` は符号付きの型である必要があります
+/// ```compile_fail
+/// use kyopro_fenwick::range_add::RangeAddFenwick;
+/// let mut bit = RangeAddFenwick::<u64>::new(3);
+/// bit.add(1, 2, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangeAddFenwick<T> {
+    b0: Fenwick<T>,
+    b1: Fenwick<T>,
+}
+
+impl<T> RangeAddFenwick<T>
+where
+    T: Copy + Default + AddAssign + Sub<Output = T> + Mul<Output = T> + Neg<Output = T> + TryFrom<usize>,
+    <T as TryFrom<usize>>::Error: Debug,
+{
+    /// 長さ `n` で、すべての要素が `T::default()` の BIT を作成します
+    pub fn new(n: usize) -> Self {
+        RangeAddFenwick {
+            b0: Fenwick::new(n + 1),
+            b1: Fenwick::new(n + 1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.b0.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn from_usize(x: usize) -> T {
+        T::try_from(x).unwrap()
+    }
+
+    /// [l, r) の要素に v を加算します
+    /// O(log n)
+    pub fn add(&mut self, l: usize, r: usize, v: T) {
+        assert!(l <= r && r <= self.len());
+        self.b0.add(l, -(v * Self::from_usize(l)));
+        self.b0.add(r, v * Self::from_usize(r));
+        self.b1.add(l, v);
+        self.b1.add(r, -v);
+    }
+
+    /// [0, r) の和を求めます
+    /// O(log n)
+    pub fn prefix_sum(&self, r: usize) -> T {
+        assert!(r <= self.len());
+        let mut res = self.b0.prefix_sum(r);
+        res += self.b1.prefix_sum(r) * Self::from_usize(r);
+        res
+    }
+
+    /// [l, r) の和を求めます
+    /// O(log n)
+    pub fn sum(&self, l: usize, r: usize) -> T {
+        assert!(l <= r);
+        self.prefix_sum(r) - self.prefix_sum(l)
+    }
+
+    /// i 番目の要素を返します
+    /// O(log n)
+    pub fn get(&self, i: usize) -> T {
+        self.sum(i, i + 1)
+    }
+}
+
+#[test]
+fn test_range_add() {
+    let n = 8;
+    let mut v = vec![0i64; n];
+    let mut bit = RangeAddFenwick::new(n);
+    for (l, r, x) in [(0, 8, 3), (2, 5, -4), (7, 8, 10), (3, 3, 100), (1, 6, 2)] {
+        bit.add(l, r, x);
+        for y in &mut v[l..r] {
+            *y += x;
+        }
+        for l in 0..=n {
+            for r in l..=n {
+                assert_eq!(bit.sum(l, r), v[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+    assert_eq!((0..n).map(|i| bit.get(i)).collect::<Vec<_>>(), v);
+}
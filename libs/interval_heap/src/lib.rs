@@ -5,15 +5,23 @@
 //! ## 実装メモ
 //! [両端優先度付きキューのInterval-Heap実装](https://natsugiri.hatenablog.com/entry/2016/10/10/035445) を参考にすると良い。
 //! この実装では、偶数 index に min heap、奇数 index に max heap を持つ点が異なるので注意。
-use std::cmp::Reverse;
+//!
+//! 片側だけの優先度付きキューで任意の要素を削除したい場合は `removable_heap::RemovableHeap` を使います。
+pub mod removable_heap;
+
+use std::{
+    cmp::Reverse,
+    iter::FromIterator,
+    ops::{Deref, DerefMut},
+};
 
 /// Interval Heap (両端優先度付きキュー)です。
 #[derive(Clone, Debug)]
-pub struct IntervalHeap<T: PartialOrd> {
+pub struct IntervalHeap<T: Ord> {
     data: Vec<T>,
 }
 
-impl<T: PartialOrd> IntervalHeap<T> {
+impl<T: Ord> IntervalHeap<T> {
     pub fn new() -> IntervalHeap<T> {
         IntervalHeap { data: Vec::new() }
     }
@@ -21,6 +29,13 @@ impl<T: PartialOrd> IntervalHeap<T> {
     pub fn with_capacity(n: usize) -> IntervalHeap<T> {
         IntervalHeap { data: Vec::with_capacity(n) }
     }
+    /// vector から作成します。
+    /// O(n)
+    pub fn from_vec(data: Vec<T>) -> IntervalHeap<T> {
+        let mut heap = IntervalHeap { data };
+        heap.heapify();
+        heap
+    }
     #[inline]
     pub fn len(&self) -> usize {
         self.data.len()
@@ -52,6 +67,27 @@ impl<T: PartialOrd> IntervalHeap<T> {
             self.data.first()
         }
     }
+    /// 最小値の可変参照を返します。
+    /// 戻り値が drop されたときにヒープの条件を満たすように並べ替えます。
+    /// Heap が空の場合は None です。
+    pub fn peek_min_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self, index: 0 })
+        }
+    }
+    /// 最大値の可変参照を返します。
+    /// 戻り値が drop されたときにヒープの条件を満たすように並べ替えます。
+    /// Heap が空の場合は None です。
+    pub fn peek_max_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        let index = match self.data.len() {
+            0 => return None,
+            1 => 0,
+            _ => 1,
+        };
+        Some(PeekMut { heap: self, index })
+    }
     /// 最小値を返します。
     /// Heap が空の場合は None です。
     #[allow(dead_code)]
@@ -96,7 +132,7 @@ impl<T: PartialOrd> IntervalHeap<T> {
                 let mut k = (i << 1) + 2;
                 // 子のうち小さい方が swap 候補
                 if k + 2 < n && unsafe { self.data.get_unchecked(k + 2) } < unsafe { self.data.get_unchecked(k) } {
-                    k = k + 2;
+                    k += 2;
                 }
                 if unsafe { self.data.get_unchecked(i) } > unsafe { self.data.get_unchecked(k) } {
                     self.data.swap(i, k);
@@ -112,16 +148,22 @@ impl<T: PartialOrd> IntervalHeap<T> {
                 }
             }
         } else {
-            while (i << 1) + 1 < n {
-                let mut k = (i << 1) + 1;
-                // 子のうち大きい方が swap 候補
-                if k + 2 < n && unsafe { self.data.get_unchecked(k + 2) } > unsafe { self.data.get_unchecked(k) } {
-                    k = k + 2;
+            while (i << 1) < n {
+                // 子の max (要素が 1 つだけの子はその要素) のうち大きい方が swap 候補
+                // 末尾の葉が要素 1 つだけのときは、その要素も max 側の子として比較する
+                let mut k = ((i << 1) + 1).min(n - 1);
+                let k2 = ((i << 1) + 3).min(n - 1);
+                if (i << 1) + 2 < n && unsafe { self.data.get_unchecked(k2) } > unsafe { self.data.get_unchecked(k) } {
+                    k = k2;
                 }
                 if unsafe { self.data.get_unchecked(i) } < unsafe { self.data.get_unchecked(k) } {
                     self.data.swap(i, k);
+                    if k & 1 == 0 {
+                        // 要素が 1 つだけのノードは葉
+                        break;
+                    }
                     i = k;
-                    if i > 0 && unsafe { self.data.get_unchecked(i) } < unsafe { self.data.get_unchecked(i - 1) } {
+                    if unsafe { self.data.get_unchecked(i) } < unsafe { self.data.get_unchecked(i - 1) } {
                         self.data.swap(i, i - 1);
                     }
                 } else {
@@ -158,6 +200,167 @@ impl<T: PartialOrd> IntervalHeap<T> {
     pub fn clear(&mut self) {
         self.data.clear();
     }
+    /// データ x を 1 つ削除します。
+    /// x が存在しなかった場合は false を返します。
+    /// O(n) (x の検索。削除後の並べ替えは O(log n))
+    pub fn remove(&mut self, x: &T) -> bool {
+        let i = match self.data.iter().position(|y| y == x) {
+            Some(i) => i,
+            None => return false,
+        };
+        self.data.swap_remove(i);
+        let n = self.data.len();
+        if i < n {
+            // 末尾の要素が移ってきた i のノードだけを整える
+            let a = i & !1;
+            if a + 1 < n && self.data[a] > self.data[a + 1] {
+                self.data.swap(a, a + 1);
+            }
+            self.up(a);
+            if a + 1 < n {
+                self.up(a + 1);
+            }
+            self.down(a);
+            if a + 1 < n {
+                self.down(a + 1);
+            }
+        }
+        true
+    }
+    /// 要素を任意の順で列挙します。
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+    /// すべての要素を任意の順で取り出します。
+    pub fn drain(&mut self) -> std::vec::Drain<'_, T> {
+        self.data.drain(..)
+    }
+    /// 要素を任意の順で並べた vector を返します。
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+    /// 昇順に並べた vector を返します。
+    /// O(n log n)
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.len());
+        while let Some(x) = self.pop_min() {
+            res.push(x);
+        }
+        res
+    }
+    /// ヒープの条件を満たしているか検査します。
+    /// 条件を満たしていない場合は panic します。
+    /// 検査するのは debug build のときだけで O(n) かかります。release build では何もしません。
+    pub fn check_invariant(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let n = self.data.len();
+        for i in 0..n {
+            if i & 1 == 0 && i + 1 < n {
+                assert!(self.data[i] <= self.data[i + 1], "min > max at {}", i);
+            }
+            if i >= 2 {
+                let p = Self::parent(i);
+                assert!(self.data[p] <= self.data[i], "parent min > data[{}]", i);
+                assert!(self.data[i] <= self.data[p + 1], "parent max < data[{}]", i);
+            }
+        }
+    }
+    /// data 全体をヒープの条件を満たすように並べ替えます。
+    /// O(n)
+    fn heapify(&mut self) {
+        let n = self.data.len();
+        // 葉に近いノードから順に、区間 [min, max] を整えて下ろす
+        for i in (0..(n + 1) / 2).rev() {
+            let (a, b) = (2 * i, 2 * i + 1);
+            if b < n && self.data[a] > self.data[b] {
+                self.data.swap(a, b);
+            }
+            self.down(a);
+            if b < n {
+                self.down(b);
+            }
+        }
+    }
+}
+
+impl<T: Ord> Default for IntervalHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for IntervalHeap<T> {
+    /// O(n)
+    fn from(v: Vec<T>) -> Self {
+        Self::from_vec(v)
+    }
+}
+
+impl<T: Ord> FromIterator<T> for IntervalHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T: Ord> Extend<T> for IntervalHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<T: Ord> IntoIterator for IntervalHeap<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+    /// 要素を任意の順で列挙します。
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a IntervalHeap<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// `IntervalHeap::peek_min_mut`, `IntervalHeap::peek_max_mut` の戻り値です。
+/// drop されたときにヒープの条件を満たすように並べ替えます。
+pub struct PeekMut<'a, T: Ord> {
+    heap: &'a mut IntervalHeap<T>,
+    index: usize,
+}
+
+impl<T: Ord> Deref for PeekMut<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.heap.data[self.index]
+    }
+}
+
+impl<T: Ord> DerefMut for PeekMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.heap.data[self.index]
+    }
+}
+
+impl<T: Ord> Drop for PeekMut<'_, T> {
+    fn drop(&mut self) {
+        // 根の [min, max] を整えてから両側を下ろす
+        let data = &mut self.heap.data;
+        if data.len() >= 2 {
+            if data[0] > data[1] {
+                data.swap(0, 1);
+            }
+            self.heap.down(0);
+            self.heap.down(1);
+        }
+    }
 }
 
 #[test]
@@ -207,14 +410,164 @@ fn test_interval_heap_pop_max() {
     assert_eq!(heap.pop_min(), None);
 }
 
+#[test]
+fn test_interval_heap_peek_max_mut_lone_leaf() {
+    // data = [1, 5, 3] で、末尾の葉は要素 3 だけのノード
+    let mut heap = IntervalHeap::new();
+    heap.push(1);
+    heap.push(5);
+    heap.push(3);
+    // 最大値を下げると、要素 1 つだけの葉の 3 が根の max に上がる
+    *heap.peek_max_mut().unwrap() = 2;
+    heap.check_invariant();
+    assert_eq!(heap.pop_max(), Some(3));
+    assert_eq!(heap.pop_max(), Some(2));
+    assert_eq!(heap.pop_max(), Some(1));
+    assert_eq!(heap.pop_max(), None);
+}
+
+#[test]
+fn test_interval_heap_remove() {
+    use kyopro_utils::XorShift;
+    let mut heap = IntervalHeap::<i32>::new();
+    let mut naive: Vec<i32> = vec![];
+    let mut rng = XorShift::new(2463534242);
+    for _ in 0..2000 {
+        let x = rng.next_u64();
+        let v = (x % 30) as i32;
+        match x % 5 {
+            0 | 1 => {
+                heap.push(v);
+                naive.push(v);
+            }
+            2 | 3 => {
+                let pos = naive.iter().position(|&y| y == v);
+                if let Some(i) = pos {
+                    naive.swap_remove(i);
+                }
+                assert_eq!(heap.remove(&v), pos.is_some());
+            }
+            _ => {
+                naive.sort();
+                if x >> 3 & 1 == 0 {
+                    assert_eq!(heap.pop_min(), if naive.is_empty() { None } else { Some(naive.remove(0)) });
+                } else {
+                    assert_eq!(heap.pop_max(), naive.pop());
+                }
+            }
+        }
+        heap.check_invariant();
+        assert_eq!(heap.peek_min(), naive.iter().min());
+        assert_eq!(heap.peek_max(), naive.iter().max());
+    }
+    naive.sort();
+    assert_eq!(heap.into_sorted_vec(), naive);
+}
+
+#[cfg(test)]
+mod property_tests {
+    use super::IntervalHeap;
+    use kyopro_utils::XorShift;
+
+    fn sorted<T: Ord + Clone>(heap: &IntervalHeap<T>) -> Vec<T> {
+        let mut v: Vec<T> = heap.iter().cloned().collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let mut rng = XorShift::new(2463534242);
+        for n in 0..50 {
+            let v: Vec<u64> = (0..n).map(|_| rng.rand(10)).collect();
+            let heap = IntervalHeap::from_vec(v.clone());
+            heap.check_invariant();
+            let mut expected = v.clone();
+            expected.sort();
+            assert_eq!(heap.clone().into_sorted_vec(), expected);
+            let collected: IntervalHeap<u64> = v.into_iter().collect();
+            collected.check_invariant();
+            assert_eq!(sorted(&collected), expected);
+        }
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut rng = XorShift::new(88172645463325252);
+        let mut heap = IntervalHeap::new();
+        // 昇順に並べた参照実装
+        let mut naive: Vec<i64> = vec![];
+        for _ in 0..5000 {
+            let v = rng.rand(50) as i64 - 25;
+            match rng.rand(9) {
+                0 | 1 => {
+                    heap.push(v);
+                    let i = naive.partition_point(|&x| x < v);
+                    naive.insert(i, v);
+                }
+                2 => assert_eq!(heap.pop_min(), if naive.is_empty() { None } else { Some(naive.remove(0)) }),
+                3 => assert_eq!(heap.pop_max(), naive.pop()),
+                4 => {
+                    let pos = naive.iter().position(|&x| x == v);
+                    if let Some(i) = pos {
+                        naive.remove(i);
+                    }
+                    assert_eq!(heap.remove(&v), pos.is_some());
+                }
+                5 => {
+                    if let Some(mut x) = heap.peek_min_mut() {
+                        *x = v;
+                        naive.remove(0);
+                        let i = naive.partition_point(|&y| y < v);
+                        naive.insert(i, v);
+                    }
+                }
+                6 => {
+                    if let Some(mut x) = heap.peek_max_mut() {
+                        *x = v;
+                        naive.pop();
+                        let i = naive.partition_point(|&y| y < v);
+                        naive.insert(i, v);
+                    }
+                }
+                7 => {
+                    let k = rng.rand(4) as usize;
+                    let items: Vec<i64> = (0..k).map(|_| rng.rand(50) as i64 - 25).collect();
+                    heap.extend(items.iter().copied());
+                    naive.extend(items);
+                    naive.sort();
+                }
+                _ => {
+                    if rng.rand(20) == 0 {
+                        let mut drained: Vec<i64> = heap.drain().collect();
+                        drained.sort();
+                        assert_eq!(drained, naive);
+                        naive.clear();
+                    }
+                }
+            }
+            heap.check_invariant();
+            assert_eq!(heap.len(), naive.len());
+            assert_eq!(heap.peek_min(), naive.first());
+            assert_eq!(heap.peek_max(), naive.last());
+        }
+        assert_eq!(sorted(&heap), naive);
+        let mut v = heap.clone().into_vec();
+        v.sort();
+        assert_eq!(v, naive);
+        assert_eq!((&heap).into_iter().count(), naive.len());
+        assert_eq!(heap.into_sorted_vec(), naive);
+    }
+}
+
 /// 容量制限付きの最大値優先キューです。
 #[derive(Clone, Debug)]
-pub struct LimitedMaximumHeap<T: PartialOrd> {
+pub struct LimitedMaximumHeap<T: Ord> {
     heap: IntervalHeap<T>,
     limit: usize,
 }
 
-impl<T: PartialOrd> LimitedMaximumHeap<T> {
+impl<T: Ord> LimitedMaximumHeap<T> {
     /// コンストラクタ
     /// + limit: 最大容量
     pub fn new(limit: usize) -> LimitedMaximumHeap<T> {
@@ -265,11 +618,11 @@ impl<T: PartialOrd> LimitedMaximumHeap<T> {
 
 /// 容量制限付きの最小値優先キューです。
 #[derive(Clone, Debug)]
-pub struct LimitedMinimumHeap<T: PartialOrd> {
+pub struct LimitedMinimumHeap<T: Ord> {
     heap: LimitedMaximumHeap<Reverse<T>>,
 }
 
-impl<T: PartialOrd> LimitedMinimumHeap<T> {
+impl<T: Ord> LimitedMinimumHeap<T> {
     pub fn new(limit: usize) -> LimitedMinimumHeap<T> {
         LimitedMinimumHeap { heap: LimitedMaximumHeap::new(limit) }
     }
@@ -0,0 +1,135 @@
+//! 削除可能な優先度付きキューです。
+//! 削除する要素を別の heap に積んでおき、先頭に来たときにまとめて取り除きます (遅延削除)。
+//!
+//! `BinaryHeap` と同じく最大値が先頭です。最小値を先頭にする場合は `Reverse` で包みます。
+//!
+//! ## Example
+//! ```
+//! use kyopro_interval_heap::removable_heap::RemovableHeap;
+//! let mut que = RemovableHeap::new();
+//! que.push(3);
+//! que.push(5);
+//! que.push(1);
+//! que.remove(5);
+//! assert_eq!(que.peek(), Some(&3));
+//! assert_eq!(que.len(), 2);
+//! assert_eq!(que.pop(), Some(3));
+//! assert_eq!(que.pop(), Some(1));
+//! assert_eq!(que.pop(), None);
+//! ```
+use std::collections::BinaryHeap;
+
+#[derive(Clone, Debug)]
+pub struct RemovableHeap<T: Ord> {
+    que: BinaryHeap<T>,
+    /// 削除予定の要素
+    del: BinaryHeap<T>,
+}
+
+impl<T: Ord> RemovableHeap<T> {
+    pub fn new() -> RemovableHeap<T> {
+        RemovableHeap {
+            que: BinaryHeap::new(),
+            del: BinaryHeap::new(),
+        }
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.que.len() - self.del.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// データ x を追加します。
+    #[inline]
+    pub fn push(&mut self, x: T) {
+        self.que.push(x);
+    }
+    /// データ x を 1 つ削除します。
+    /// x は heap に含まれている必要があります (含まれていない場合、以降の結果は不定です)。
+    /// 削除予定の要素数が heap の要素数を超えた場合は panic します。
+    #[inline]
+    pub fn remove(&mut self, x: T) {
+        self.del.push(x);
+        assert!(self.del.len() <= self.que.len(), "removed more elements than pushed");
+    }
+    /// 削除予定の要素を先頭から取り除きます。
+    fn clean(&mut self) {
+        while let (Some(a), Some(b)) = (self.que.peek(), self.del.peek()) {
+            if a != b {
+                break;
+            }
+            self.que.pop();
+            self.del.pop();
+        }
+    }
+    /// 最大値の参照を返します。
+    /// Heap が空の場合は None です。
+    pub fn peek(&mut self) -> Option<&T> {
+        self.clean();
+        self.que.peek()
+    }
+    /// 最大値を返します。
+    /// Heap が空の場合は None です。
+    pub fn pop(&mut self) -> Option<T> {
+        self.clean();
+        self.que.pop()
+    }
+    /// ヒープをクリアします。
+    #[inline]
+    pub fn clear(&mut self) {
+        self.que.clear();
+        self.del.clear();
+    }
+}
+
+impl<T: Ord> Default for RemovableHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_removable_heap_remove_too_many() {
+    let mut que = RemovableHeap::new();
+    que.push(1);
+    que.remove(1);
+    que.remove(2);
+}
+
+#[test]
+fn test_removable_heap() {
+    use kyopro_utils::XorShift;
+    use std::cmp::Reverse;
+    let mut que = RemovableHeap::new();
+    let mut naive: Vec<i32> = vec![];
+    let mut rng = XorShift::new(88172645463325252);
+    for _ in 0..1000 {
+        let x = rng.next_u64();
+        let v = (x % 20) as i32;
+        match x % 4 {
+            0 | 1 => {
+                que.push(Reverse(v));
+                naive.push(v);
+            }
+            2 => {
+                if let Some(i) = naive.iter().position(|&y| y == v) {
+                    naive.swap_remove(i);
+                    que.remove(Reverse(v));
+                }
+            }
+            _ => {
+                let min = naive.iter().copied().min();
+                if let Some(m) = min {
+                    let i = naive.iter().position(|&y| y == m).unwrap();
+                    naive.swap_remove(i);
+                }
+                assert_eq!(que.pop().map(|Reverse(v)| v), min);
+            }
+        }
+        assert_eq!(que.len(), naive.len());
+        assert_eq!(que.peek().map(|r| r.0), naive.iter().copied().min());
+    }
+}
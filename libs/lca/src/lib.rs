@@ -16,26 +16,21 @@
 //! lca.lca(0, N - 1);
 //! ```
 #![allow(non_snake_case)]
+use kyopro_sparse_table::SparseTable;
 use std::mem;
 
+/// (深さ, 頂点) の小さい方を返します
+type MinDepth = fn(&(usize, usize), &(usize, usize)) -> (usize, usize);
+
 pub struct LCA {
     g: Vec<Vec<usize>>,
     depth: Vec<usize>,
     tour_v: Vec<usize>,
     v_to_i: Vec<usize>,
-    tour_depth: Vec<usize>,
-    sparse_table: Vec<Vec<usize>>,
+    // オイラーツアー上の (深さ, 頂点) の区間最小
+    sparse_table: Option<SparseTable<(usize, usize), MinDepth>>,
 }
 
-/// v を表すために必要な bit size を返します
-fn bitsize(mut v: usize) -> usize {
-    let mut res = 0;
-    while v > 0 {
-        res += 1;
-        v >>= 1;
-    }
-    res
-}
 impl LCA {
     /// インスタンスを作成
     ///
@@ -46,8 +41,7 @@ impl LCA {
             depth: vec![],
             tour_v: vec![],
             v_to_i: vec![],
-            tour_depth: vec![],
-            sparse_table: vec![],
+            sparse_table: None,
         }
     }
 
@@ -61,33 +55,9 @@ impl LCA {
         self.v_to_i = vec![!0; self.g.len()];
         self.depth = vec![!0; self.g.len()];
         self.dfs(0, !0, 0);
-        self.tour_depth = self.tour_v.iter().map(|&v| self.depth[v]).collect();
-
-        let N = self.tour_v.len();
-        let U = bitsize(N);
-        // sparse table
-        // 区間最小の深さをもつ頂点 index を記録する
-        let mut sp = vec![vec![]; 1];
-        for i in 0..N {
-            sp[0].push(i);
-        }
-        for i in 1..U {
-            let prev = &sp[i - 1];
-            let width = 1 << (i - 1);
-            let next_size = prev.len() - width;
-
-            let mut tmp = vec![!0; next_size];
-            for j in 0..next_size {
-                let n1 = prev[j];
-                let n2 = prev[j + width];
-                let n1d = self.tour_depth[n1];
-                let n2d = self.tour_depth[n2];
-                // 小さい深さの頂点 index を記録
-                tmp[j] = if n1d < n2d { n1 } else { n2 };
-            }
-            sp.push(tmp);
-        }
-        self.sparse_table = sp;
+        let tour = self.tour_v.iter().map(|&v| (self.depth[v], v)).collect();
+        let f: MinDepth = |a, b| *a.min(b);
+        self.sparse_table = Some(SparseTable::new(tour, f));
     }
 
     // build euler tour
@@ -108,8 +78,7 @@ impl LCA {
     /// 頂点 u, v の LCA を求めます
     pub fn lca(&self, u: usize, v: usize) -> usize {
         // 初期化済みチェック
-        let initialized = !self.sparse_table.is_empty();
-        assert!(initialized);
+        let sparse_table = self.sparse_table.as_ref().expect("call init before lca");
 
         if u == v {
             return u;
@@ -120,13 +89,7 @@ impl LCA {
             mem::swap(&mut ix, &mut iy);
         }
         // オイラーツアー上の区間最小の深さをもつ頂点が LCA
-        let items = iy - ix + 1;
-        let L = bitsize(items) - 1;
-        let p1 = self.sparse_table[L][ix];
-        let p2 = self.sparse_table[L][1 + iy - (1 << L)];
-
-        let idx = if self.tour_depth[p1] < self.tour_depth[p2] { p1 } else { p2 };
-        self.tour_v[idx]
+        sparse_table.query(ix, iy + 1).1
     }
     /// 頂点 u, v の距離を求めます
     pub fn dist(&self, u: usize, v: usize) -> usize {
@@ -0,0 +1,498 @@
+//! 遅延評価 segment tree です
+//! 実装は ac-library の lazysegtree と同じです
+//!
+//! よく使う作用付きモノイドは `RangeAddRangeMin` などとして用意しています
+//!
+//! ## Example
+//! ```
+//! use kyopro_segmenttree::lazy_segtree::{LazySegTree, RangeAddRangeMax};
+//! // 区間加算、区間最大値
+//! let mut seg: LazySegTree<RangeAddRangeMax<i32>> = LazySegTree::from(vec![0; 10]);
+//! seg.apply(0..5, 2);
+//! seg.apply(4..8, 3);
+//! assert_eq!(seg.all_prod(), 5);
+//! assert_eq!(seg.prod(5..), 3);
+//! ```
+use crate::{
+    monoid::{BoundedAbove, BoundedBelow, MapMonoid, Max, Min, Monoid, One, SumWithSize, Zero},
+    to_half_open,
+};
+use std::{
+    marker::PhantomData,
+    ops::{Add, Mul, RangeBounds},
+};
+
+type S<F> = <<F as MapMonoid>::M as Monoid>::S;
+
+pub struct LazySegTree<F: MapMonoid> {
+    n: usize,
+    size: usize,
+    log: usize,
+    d: Vec<S<F>>,
+    lz: Vec<F::F>,
+}
+
+impl<F: MapMonoid> From<Vec<S<F>>> for LazySegTree<F> {
+    fn from(v: Vec<S<F>>) -> Self {
+        let n = v.len();
+        let mut log = 0;
+        while (1 << log) < n {
+            log += 1;
+        }
+        let size = 1 << log;
+        let mut d = vec![F::identity_element(); 2 * size];
+        d[size..size + n].clone_from_slice(&v);
+        let mut res = LazySegTree { n, size, log, d, lz: vec![F::identity_map(); size] };
+        for i in (1..size).rev() {
+            res.update(i);
+        }
+        res
+    }
+}
+
+impl<F: MapMonoid> LazySegTree<F> {
+    /// 長さ `n` で、すべての要素が単位元の segment tree を作成します
+    pub fn new(n: usize) -> Self {
+        vec![F::identity_element(); n].into()
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// p 番目の要素を x に更新します
+    /// O(log n)
+    pub fn set(&mut self, p: usize, x: S<F>) {
+        assert!(p < self.n);
+        let p = p + self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.d[p] = x;
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    /// p 番目の要素を返します
+    /// O(log n)
+    pub fn get(&mut self, p: usize) -> S<F> {
+        assert!(p < self.n);
+        let p = p + self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.d[p].clone()
+    }
+
+    /// 区間の演算結果を返します
+    /// O(log n)
+    pub fn prod<R: RangeBounds<usize>>(&mut self, range: R) -> S<F> {
+        let (l, r) = to_half_open(&range, self.n);
+        if l == r {
+            return F::identity_element();
+        }
+        let mut l = l + self.size;
+        let mut r = r + self.size;
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+        let mut sml = F::identity_element();
+        let mut smr = F::identity_element();
+        while l < r {
+            if l & 1 == 1 {
+                sml = F::binary_operation(&sml, &self.d[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                smr = F::binary_operation(&self.d[r], &smr);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        F::binary_operation(&sml, &smr)
+    }
+
+    /// 全体の演算結果を返します
+    /// O(1)
+    pub fn all_prod(&self) -> S<F> {
+        self.d[1].clone()
+    }
+
+    /// 区間の各要素に作用素 f を適用します
+    /// O(log n)
+    pub fn apply<R: RangeBounds<usize>>(&mut self, range: R, f: F::F) {
+        let (l, r) = to_half_open(&range, self.n);
+        if l == r {
+            return;
+        }
+        let l = l + self.size;
+        let r = r + self.size;
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+        {
+            let mut l = l;
+            let mut r = r;
+            while l < r {
+                if l & 1 == 1 {
+                    self.all_apply(l, &f);
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    self.all_apply(r, &f);
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+        for i in 1..=self.log {
+            if ((l >> i) << i) != l {
+                self.update(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.update((r - 1) >> i);
+            }
+        }
+    }
+
+    /// `g(prod(l..r)) = true` となる最大の r を返します (g が単調な場合)
+    /// `g(単位元) = true` である必要があります
+    /// O(log n)
+    pub fn max_right<G: Fn(&S<F>) -> bool>(&mut self, l: usize, g: G) -> usize {
+        assert!(l <= self.n);
+        assert!(g(&F::identity_element()));
+        if l == self.n {
+            return self.n;
+        }
+        let mut l = l + self.size;
+        for i in (1..=self.log).rev() {
+            self.push(l >> i);
+        }
+        let mut sm = F::identity_element();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            if !g(&F::binary_operation(&sm, &self.d[l])) {
+                while l < self.size {
+                    self.push(l);
+                    l *= 2;
+                    let res = F::binary_operation(&sm, &self.d[l]);
+                    if g(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = F::binary_operation(&sm, &self.d[l]);
+            l += 1;
+            if l.is_power_of_two() {
+                break;
+            }
+        }
+        self.n
+    }
+
+    /// `g(prod(l..r)) = true` となる最小の l を返します (g が単調な場合)
+    /// `g(単位元) = true` である必要があります
+    /// O(log n)
+    pub fn min_left<G: Fn(&S<F>) -> bool>(&mut self, r: usize, g: G) -> usize {
+        assert!(r <= self.n);
+        assert!(g(&F::identity_element()));
+        if r == 0 {
+            return 0;
+        }
+        let mut r = r + self.size;
+        for i in (1..=self.log).rev() {
+            self.push((r - 1) >> i);
+        }
+        let mut sm = F::identity_element();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1;
+            }
+            if !g(&F::binary_operation(&self.d[r], &sm)) {
+                while r < self.size {
+                    self.push(r);
+                    r = 2 * r + 1;
+                    let res = F::binary_operation(&self.d[r], &sm);
+                    if g(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = F::binary_operation(&self.d[r], &sm);
+            if r.is_power_of_two() {
+                break;
+            }
+        }
+        0
+    }
+
+    fn update(&mut self, k: usize) {
+        self.d[k] = F::binary_operation(&self.d[2 * k], &self.d[2 * k + 1]);
+    }
+    fn all_apply(&mut self, k: usize, f: &F::F) {
+        self.d[k] = F::mapping(f, &self.d[k]);
+        if k < self.size {
+            self.lz[k] = F::composition(f, &self.lz[k]);
+        }
+    }
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.lz[k], F::identity_map());
+        self.all_apply(2 * k, &f);
+        self.all_apply(2 * k + 1, &f);
+    }
+}
+
+/// 区間加算・区間最小値
+///
+/// `T::max_value()` は単位元 (要素なし) として予約しています
+/// 値が `T::max_value()` の要素には加算が反映されないので、要素として使わないでください
+pub struct RangeAddRangeMin<T>(PhantomData<fn() -> T>);
+impl<T> MapMonoid for RangeAddRangeMin<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Zero + BoundedAbove,
+{
+    type M = Min<T>;
+    type F = T;
+    fn identity_map() -> T {
+        T::zero()
+    }
+    fn mapping(&f: &T, &x: &T) -> T {
+        // 単位元 (最大値) はそのままにする
+        if x == T::max_value() {
+            x
+        } else {
+            f + x
+        }
+    }
+    fn composition(&f: &T, &g: &T) -> T {
+        f + g
+    }
+}
+
+/// 区間加算・区間最大値
+///
+/// `T::min_value()` は単位元 (要素なし) として予約しています
+/// 値が `T::min_value()` の要素には加算が反映されないので、要素として使わないでください
+pub struct RangeAddRangeMax<T>(PhantomData<fn() -> T>);
+impl<T> MapMonoid for RangeAddRangeMax<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Zero + BoundedBelow,
+{
+    type M = Max<T>;
+    type F = T;
+    fn identity_map() -> T {
+        T::zero()
+    }
+    fn mapping(&f: &T, &x: &T) -> T {
+        // 単位元 (最小値) はそのままにする
+        if x == T::min_value() {
+            x
+        } else {
+            f + x
+        }
+    }
+    fn composition(&f: &T, &g: &T) -> T {
+        f + g
+    }
+}
+
+/// 区間加算・区間和
+/// 要素は `(値, 1)` で初期化します
+pub struct RangeAddRangeSum<T>(PhantomData<fn() -> T>);
+impl<T> MapMonoid for RangeAddRangeSum<T>
+where
+    T: Copy + Add<Output = T> + Mul<Output = T> + Zero,
+{
+    type M = SumWithSize<T>;
+    type F = T;
+    fn identity_map() -> T {
+        T::zero()
+    }
+    fn mapping(&f: &T, &(sum, size): &(T, T)) -> (T, T) {
+        // 区間それぞれに +f するので、合計は size*f 増える
+        (sum + f * size, size)
+    }
+    fn composition(&f: &T, &g: &T) -> T {
+        f + g
+    }
+}
+
+/// 区間代入・区間和
+/// 要素は `(値, 1)` で初期化します
+pub struct RangeAssignRangeSum<T>(PhantomData<fn() -> T>);
+impl<T> MapMonoid for RangeAssignRangeSum<T>
+where
+    T: Copy + Add<Output = T> + Mul<Output = T> + Zero,
+{
+    type M = SumWithSize<T>;
+    type F = Option<T>;
+    fn identity_map() -> Option<T> {
+        None
+    }
+    fn mapping(f: &Option<T>, &(sum, size): &(T, T)) -> (T, T) {
+        match *f {
+            Some(a) => (a * size, size),
+            None => (sum, size),
+        }
+    }
+    fn composition(f: &Option<T>, g: &Option<T>) -> Option<T> {
+        // 後から代入した f が優先
+        f.or(*g)
+    }
+}
+
+/// 区間アフィン変換・区間和
+/// 作用素 `(a, b)` は `x -> a*x + b` を表します
+/// 要素は `(値, 1)` で初期化します
+pub struct RangeAffineRangeSum<T>(PhantomData<fn() -> T>);
+impl<T> MapMonoid for RangeAffineRangeSum<T>
+where
+    T: Copy + Add<Output = T> + Mul<Output = T> + Zero + One,
+{
+    type M = SumWithSize<T>;
+    type F = (T, T);
+    fn identity_map() -> (T, T) {
+        (T::one(), T::zero())
+    }
+    fn mapping(&(a, b): &(T, T), &(sum, size): &(T, T)) -> (T, T) {
+        (a * sum + b * size, size)
+    }
+    fn composition(&(a, b): &(T, T), &(c, d): &(T, T)) -> (T, T) {
+        // f(g(x)) = a(cx + d) + b
+        (a * c, a * d + b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kyopro_utils::XorShift;
+
+    /// ランダムな区間 [l, r) (0 <= l <= r <= n) を返します
+    fn random_range(rng: &mut XorShift, n: usize) -> (usize, usize) {
+        let l = rng.rand(n as u64 + 1) as usize;
+        let r = rng.rand(n as u64 + 1) as usize;
+        (l.min(r), l.max(r))
+    }
+
+    #[test]
+    fn test_add_min_max() {
+        let mut rng = XorShift::new(88172645463325252);
+        let n = 20;
+        let mut v: Vec<i64> = (0..n).map(|_| rng.rand(100) as i64 - 50).collect();
+        let mut smin: LazySegTree<RangeAddRangeMin<i64>> = LazySegTree::from(v.clone());
+        let mut smax: LazySegTree<RangeAddRangeMax<i64>> = LazySegTree::from(v.clone());
+        for _ in 0..500 {
+            let (l, r) = random_range(&mut rng, n);
+            if rng.rand(2) == 0 {
+                let x = rng.rand(21) as i64 - 10;
+                v[l..r].iter_mut().for_each(|e| *e += x);
+                smin.apply(l..r, x);
+                smax.apply(l..r, x);
+            } else {
+                assert_eq!(smin.prod(l..r), v[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                assert_eq!(smax.prod(l..r), v[l..r].iter().copied().max().unwrap_or(i64::MIN));
+            }
+        }
+        for (i, &x) in v.iter().enumerate() {
+            assert_eq!(smin.get(i), x);
+        }
+    }
+
+    #[test]
+    fn test_sum_presets() {
+        let mut rng = XorShift::new(2463534242);
+        let n = 17;
+        let mut v: Vec<i64> = (0..n).map(|_| rng.rand(10) as i64).collect();
+        let init: Vec<(i64, i64)> = v.iter().map(|&x| (x, 1)).collect();
+        let mut add: LazySegTree<RangeAddRangeSum<i64>> = LazySegTree::from(init.clone());
+        let mut assign: LazySegTree<RangeAssignRangeSum<i64>> = LazySegTree::from(init.clone());
+        let mut affine: LazySegTree<RangeAffineRangeSum<i64>> = LazySegTree::from(init);
+        // add と assign は別の列として管理する
+        let mut w = v.clone();
+        let mut u = v.clone();
+        for _ in 0..500 {
+            let (l, r) = random_range(&mut rng, n);
+            match rng.rand(4) {
+                0 => {
+                    let x = rng.rand(7) as i64 - 3;
+                    v[l..r].iter_mut().for_each(|e| *e += x);
+                    add.apply(l..r, x);
+                }
+                1 => {
+                    let x = rng.rand(7) as i64 - 3;
+                    w[l..r].iter_mut().for_each(|e| *e = x);
+                    assign.apply(l..r, Some(x));
+                }
+                2 => {
+                    let a = rng.rand(3) as i64 - 1;
+                    let b = rng.rand(7) as i64 - 3;
+                    u[l..r].iter_mut().for_each(|e| *e = a * *e + b);
+                    affine.apply(l..r, (a, b));
+                }
+                _ => {
+                    assert_eq!(add.prod(l..r).0, v[l..r].iter().sum::<i64>());
+                    assert_eq!(assign.prod(l..r).0, w[l..r].iter().sum::<i64>());
+                    assert_eq!(affine.prod(l..r).0, u[l..r].iter().sum::<i64>());
+                    assert_eq!(add.prod(l..r).1, (r - l) as i64);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_right_min_left() {
+        let v: Vec<(i64, i64)> = [0, 1, 1, 0, 1, 1].iter().map(|&x| (x, 1)).collect();
+        let mut seg: LazySegTree<RangeAddRangeSum<i64>> = LazySegTree::from(v);
+        assert_eq!(seg.max_right(1, |x| x.0 <= 1), 2);
+        assert_eq!(seg.max_right(1, |x| x.0 <= 2), 4);
+        assert_eq!(seg.min_left(2, |x| x.0 <= 1), 0);
+        assert_eq!(seg.min_left(3, |x| x.0 <= 1), 2);
+        // [2, 6) に +1 して [0, 1, 2, 1, 2, 2]
+        seg.apply(2.., 1);
+        assert_eq!(seg.max_right(0, |x| x.0 <= 3), 3);
+        assert_eq!(seg.max_right(0, |x| x.0 <= 100), 6);
+        assert_eq!(seg.min_left(6, |x| x.0 <= 4), 4);
+        assert_eq!(seg.min_left(6, |x| x.0 <= 100), 0);
+    }
+
+    #[test]
+    fn test_set_get() {
+        let mut seg: LazySegTree<RangeAddRangeMin<i32>> = LazySegTree::new(5);
+        assert_eq!(seg.all_prod(), i32::MAX);
+        // 単位元の要素に加算しても単位元のまま
+        seg.apply(.., 3);
+        assert_eq!(seg.get(2), i32::MAX);
+        seg.set(2, 4);
+        seg.set(4, 1);
+        seg.apply(2..=3, 3);
+        assert_eq!(seg.get(2), 7);
+        assert_eq!(seg.prod(..4), 7);
+        assert_eq!(seg.all_prod(), 1);
+    }
+}
@@ -0,0 +1,32 @@
+//! segment tree です
+//! 載せる演算は `Monoid`, `MapMonoid` trait で指定します
+pub mod lazy_segtree;
+pub mod monoid;
+pub mod segtree;
+
+use std::ops::{Bound, RangeBounds};
+
+/// `RangeBounds` を長さ `n` の列上の半開区間 `[l, r)` に変換します
+pub fn to_half_open<R: RangeBounds<usize>>(range: &R, n: usize) -> (usize, usize) {
+    let l = match range.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(&r) => r + 1,
+        Bound::Excluded(&r) => r,
+        Bound::Unbounded => n,
+    };
+    assert!(l <= r && r <= n);
+    (l, r)
+}
+
+#[test]
+fn test_to_half_open() {
+    assert_eq!(to_half_open(&(..), 5), (0, 5));
+    assert_eq!(to_half_open(&(1..3), 5), (1, 3));
+    assert_eq!(to_half_open(&(1..=3), 5), (1, 4));
+    assert_eq!(to_half_open(&(2..), 5), (2, 5));
+    assert_eq!(to_half_open(&(..=0), 5), (0, 1));
+}
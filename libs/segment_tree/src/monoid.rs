@@ -0,0 +1,185 @@
+//! segment tree に載せる演算の trait です
+//! ac-library-rs の `Monoid`, `MapMonoid` と同じ形にしています
+use kyopro_math::{gcd, Integer};
+use std::{
+    marker::PhantomData,
+    ops::{Add, BitXor, Mul},
+};
+
+/// モノイド
+/// 演算は可換でなくても構いませんが、`SegmentTree2D` のように可換であることを要求するデータ構造もあります
+pub trait Monoid {
+    type S: Clone;
+    /// 単位元
+    fn identity() -> Self::S;
+    /// 二項演算 (結合則を満たすこと)
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+/// 作用付きモノイド (遅延セグ木用)
+pub trait MapMonoid {
+    type M: Monoid;
+    /// 作用素
+    type F: Clone;
+
+    fn identity_element() -> <Self::M as Monoid>::S {
+        Self::M::identity()
+    }
+    fn binary_operation(a: &<Self::M as Monoid>::S, b: &<Self::M as Monoid>::S) -> <Self::M as Monoid>::S {
+        Self::M::binary_operation(a, b)
+    }
+    /// 恒等写像 id
+    /// mapping(id, x) = x となる値
+    fn identity_map() -> Self::F;
+    /// 作用素 f を x に適用します
+    fn mapping(f: &Self::F, x: &<Self::M as Monoid>::S) -> <Self::M as Monoid>::S;
+    /// 写像の合成 f∘g (g を適用した後に f を適用する)
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F;
+}
+
+pub trait Zero {
+    fn zero() -> Self;
+}
+pub trait One {
+    fn one() -> Self;
+}
+/// 最大値を持つ型
+pub trait BoundedAbove {
+    fn max_value() -> Self;
+}
+/// 最小値を持つ型
+pub trait BoundedBelow {
+    fn min_value() -> Self;
+}
+
+// 整数型に数値 trait を実装する
+macro_rules! impl_num_traits_for_integer {
+    ($($T:ty),*) => {
+        $(
+            impl Zero for $T {
+                fn zero() -> Self {
+                    0
+                }
+            }
+            impl One for $T {
+                fn one() -> Self {
+                    1
+                }
+            }
+            impl BoundedAbove for $T {
+                fn max_value() -> Self {
+                    <$T>::MAX
+                }
+            }
+            impl BoundedBelow for $T {
+                fn min_value() -> Self {
+                    <$T>::MIN
+                }
+            }
+        )*
+    };
+}
+impl_num_traits_for_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Zero for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+}
+impl One for f64 {
+    fn one() -> Self {
+        1.0
+    }
+}
+impl BoundedAbove for f64 {
+    fn max_value() -> Self {
+        f64::INFINITY
+    }
+}
+impl BoundedBelow for f64 {
+    fn min_value() -> Self {
+        f64::NEG_INFINITY
+    }
+}
+
+/// 最小値
+pub struct Min<T>(PhantomData<fn() -> T>);
+impl<T: Copy + PartialOrd + BoundedAbove> Monoid for Min<T> {
+    type S = T;
+    fn identity() -> T {
+        T::max_value()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        if a < b {
+            *a
+        } else {
+            *b
+        }
+    }
+}
+
+/// 最大値
+pub struct Max<T>(PhantomData<fn() -> T>);
+impl<T: Copy + PartialOrd + BoundedBelow> Monoid for Max<T> {
+    type S = T;
+    fn identity() -> T {
+        T::min_value()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        if a > b {
+            *a
+        } else {
+            *b
+        }
+    }
+}
+
+/// 和
+pub struct Sum<T>(PhantomData<fn() -> T>);
+impl<T: Copy + Add<Output = T> + Zero> Monoid for Sum<T> {
+    type S = T;
+    fn identity() -> T {
+        T::zero()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        *a + *b
+    }
+}
+
+/// 最大公約数
+/// 単位元は 0 です
+pub struct Gcd<T>(PhantomData<fn() -> T>);
+impl<T: Integer + Zero> Monoid for Gcd<T> {
+    type S = T;
+    fn identity() -> T {
+        T::zero()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        gcd(*a, *b)
+    }
+}
+
+/// 排他的論理和
+pub struct Xor<T>(PhantomData<fn() -> T>);
+impl<T: Copy + BitXor<Output = T> + Zero> Monoid for Xor<T> {
+    type S = T;
+    fn identity() -> T {
+        T::zero()
+    }
+    fn binary_operation(a: &T, b: &T) -> T {
+        *a ^ *b
+    }
+}
+
+/// 区間の長さ付きの和
+/// 値は `(和, 区間の長さ)` で、葉は `(v, 1)` で初期化します
+pub struct SumWithSize<T>(PhantomData<fn() -> T>);
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + Zero> Monoid for SumWithSize<T> {
+    type S = (T, T);
+    fn identity() -> (T, T) {
+        (T::zero(), T::zero())
+    }
+    fn binary_operation(a: &(T, T), b: &(T, T)) -> (T, T) {
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
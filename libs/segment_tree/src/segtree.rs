@@ -0,0 +1,242 @@
+//! segment tree です
+//! 実装は ac-library の segtree と同じです
+//!
+//! ## Example
+//! ```
+//! use kyopro_segmenttree::{monoid::Min, segtree::SegTree};
+//! let mut seg: SegTree<Min<i32>> = SegTree::from(vec![5, 3, 7, 1, 6]);
+//! assert_eq!(seg.prod(0..3), 3);
+//! assert_eq!(seg.prod(..), 1);
+//! seg.set(3, 10);
+//! assert_eq!(seg.prod(2..=3), 7);
+//! // 先頭から見て値が 4 以上である最大の区間 [0, r)
+//! assert_eq!(seg.max_right(0, |&x| x >= 4), 1);
+//! ```
+use crate::{monoid::Monoid, to_half_open};
+use std::ops::RangeBounds;
+
+pub struct SegTree<M: Monoid> {
+    n: usize,
+    size: usize,
+    log: usize,
+    d: Vec<M::S>,
+}
+
+impl<M: Monoid> From<Vec<M::S>> for SegTree<M> {
+    fn from(v: Vec<M::S>) -> Self {
+        let n = v.len();
+        let mut log = 0;
+        while (1 << log) < n {
+            log += 1;
+        }
+        let size = 1 << log;
+        let mut d = vec![M::identity(); 2 * size];
+        d[size..size + n].clone_from_slice(&v);
+        let mut res = SegTree { n, size, log, d };
+        for i in (1..size).rev() {
+            res.update(i);
+        }
+        res
+    }
+}
+
+impl<M: Monoid> SegTree<M> {
+    /// 長さ `n` で、すべての要素が単位元の segment tree を作成します
+    pub fn new(n: usize) -> Self {
+        vec![M::identity(); n].into()
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// p 番目の要素を x に更新します
+    /// O(log n)
+    pub fn set(&mut self, p: usize, x: M::S) {
+        assert!(p < self.n);
+        let p = p + self.size;
+        self.d[p] = x;
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    /// p 番目の要素を `op(a[p], x)` に更新します
+    /// O(log n)
+    pub fn combine(&mut self, p: usize, x: M::S) {
+        let v = M::binary_operation(&self.get(p), &x);
+        self.set(p, v);
+    }
+
+    /// p 番目の要素を返します
+    /// O(1)
+    pub fn get(&self, p: usize) -> M::S {
+        assert!(p < self.n);
+        self.d[p + self.size].clone()
+    }
+
+    /// 区間の演算結果を返します
+    /// O(log n)
+    pub fn prod<R: RangeBounds<usize>>(&self, range: R) -> M::S {
+        let (l, r) = to_half_open(&range, self.n);
+        let mut sml = M::identity();
+        let mut smr = M::identity();
+        let mut l = l + self.size;
+        let mut r = r + self.size;
+        while l < r {
+            if l & 1 == 1 {
+                sml = M::binary_operation(&sml, &self.d[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                smr = M::binary_operation(&self.d[r], &smr);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        M::binary_operation(&sml, &smr)
+    }
+
+    /// 全体の演算結果を返します
+    /// O(1)
+    pub fn all_prod(&self) -> M::S {
+        self.d[1].clone()
+    }
+
+    /// `g(prod(l..r)) = true` となる最大の r を返します (g が単調な場合)
+    /// `g(単位元) = true` である必要があります
+    /// O(log n)
+    pub fn max_right<G: Fn(&M::S) -> bool>(&self, l: usize, g: G) -> usize {
+        assert!(l <= self.n);
+        assert!(g(&M::identity()));
+        if l == self.n {
+            return self.n;
+        }
+        let mut l = l + self.size;
+        let mut sm = M::identity();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            if !g(&M::binary_operation(&sm, &self.d[l])) {
+                while l < self.size {
+                    l *= 2;
+                    let res = M::binary_operation(&sm, &self.d[l]);
+                    if g(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = M::binary_operation(&sm, &self.d[l]);
+            l += 1;
+            if l.is_power_of_two() {
+                break;
+            }
+        }
+        self.n
+    }
+
+    /// `g(prod(l..r)) = true` となる最小の l を返します (g が単調な場合)
+    /// `g(単位元) = true` である必要があります
+    /// O(log n)
+    pub fn min_left<G: Fn(&M::S) -> bool>(&self, r: usize, g: G) -> usize {
+        assert!(r <= self.n);
+        assert!(g(&M::identity()));
+        if r == 0 {
+            return 0;
+        }
+        let mut r = r + self.size;
+        let mut sm = M::identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1;
+            }
+            if !g(&M::binary_operation(&self.d[r], &sm)) {
+                while r < self.size {
+                    r = 2 * r + 1;
+                    let res = M::binary_operation(&self.d[r], &sm);
+                    if g(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = M::binary_operation(&self.d[r], &sm);
+            if r.is_power_of_two() {
+                break;
+            }
+        }
+        0
+    }
+
+    fn update(&mut self, k: usize) {
+        self.d[k] = M::binary_operation(&self.d[2 * k], &self.d[2 * k + 1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SegTree;
+    use crate::monoid::{Gcd, Max, Min, Monoid, Sum, Xor};
+
+    #[test]
+    fn test_monoids() {
+        let v = vec![12u64, 18, 7, 30, 42, 6, 9];
+        let min: SegTree<Min<u64>> = SegTree::from(v.clone());
+        let max: SegTree<Max<u64>> = SegTree::from(v.clone());
+        let sum: SegTree<Sum<u64>> = SegTree::from(v.clone());
+        let gcd: SegTree<Gcd<u64>> = SegTree::from(v.clone());
+        let xor: SegTree<Xor<u64>> = SegTree::from(v.clone());
+        for l in 0..=v.len() {
+            for r in l..=v.len() {
+                let s = &v[l..r];
+                assert_eq!(min.prod(l..r), s.iter().copied().min().unwrap_or(u64::MAX));
+                assert_eq!(max.prod(l..r), s.iter().copied().max().unwrap_or(0));
+                assert_eq!(sum.prod(l..r), s.iter().sum::<u64>());
+                assert_eq!(gcd.prod(l..r), s.iter().fold(0, |g, &x| Gcd::<u64>::binary_operation(&g, &x)));
+                assert_eq!(xor.prod(l..r), s.iter().fold(0, |g, &x| g ^ x));
+            }
+        }
+        assert_eq!(gcd.prod(0..2), 6);
+        assert_eq!(gcd.prod(3..6), 6);
+        assert_eq!(gcd.all_prod(), 1);
+    }
+
+    #[test]
+    fn test_set_combine() {
+        let mut seg: SegTree<Sum<i64>> = SegTree::new(5);
+        seg.set(1, 3);
+        seg.combine(1, 4);
+        seg.combine(4, -2);
+        assert_eq!(seg.get(1), 7);
+        assert_eq!(seg.prod(..), 5);
+        assert_eq!(seg.prod(2..), -2);
+    }
+
+    #[test]
+    fn test_max_right_min_left() {
+        let v = vec![0i64, 1, 1, 0, 1, 1];
+        let seg: SegTree<Sum<i64>> = SegTree::from(v.clone());
+        for l in 0..=v.len() {
+            for x in 0..5 {
+                let naive = (l..=v.len()).filter(|&r| v[l..r].iter().sum::<i64>() <= x).max().unwrap();
+                assert_eq!(seg.max_right(l, |&s| s <= x), naive);
+            }
+        }
+        for r in 0..=v.len() {
+            for x in 0..5 {
+                let naive = (0..=r).filter(|&l| v[l..r].iter().sum::<i64>() <= x).min().unwrap();
+                assert_eq!(seg.min_left(r, |&s| s <= x), naive);
+            }
+        }
+    }
+}
@@ -1,32 +1,45 @@
 #![allow(non_snake_case)]
-///! 2次元 segment tree
-/// org: https://nyaannyaan.github.io/library/data-structure-2d/2d-segment-tree.hpp
-
-//// 2次元 segment tree
-////
-//// Example
-//// ```
-//// let mut seg = SegmentTree2D::new(3, 3, |a: usize, b: usize| a + b, 0usize);
-//// for i in 0..3 {
-////     for j in 0..3 {
-////         seg.set(i, j, i * 3 + j);
-////     }
-//// }
-//// seg.build();
-//// assert_eq!(seg.query(0, 3, 0, 3), 36);
-//// ```
-
-pub struct SegmentTree2D<T, F> {
+//! 2次元 segment tree
+//! org: https://nyaannyaan.github.io/library/data-structure-2d/2d-segment-tree.hpp
+//!
+//! 載せる演算は `kyopro_segmenttree::monoid::Monoid` で指定します
+//! 矩形の演算結果は部分矩形の結果を行・列の順序によらずに組み合わせて求めるので、演算は可換である必要があります
+//! (1 行または 1 列だけの矩形であれば、可換でなくても左から順に演算した結果になります)
+//! 4HW 個の領域を使うので、座標が大きい場合は `sparse_segtree2d::SparseSegmentTree2D` を使います
+//!
+//! ## Example
+//! ```
+//! use kyopro_segmenttree::monoid::Sum;
+//! use kyopro_segmenttree2d::SegmentTree2D;
+//! let mut seg: SegmentTree2D<Sum<usize>> = SegmentTree2D::new(3, 3);
+//! for i in 0..3 {
+//!     for j in 0..3 {
+//!         seg.set(i, j, i * 3 + j);
+//!     }
+//! }
+//! seg.build();
+//! assert_eq!(seg.query(.., ..), 36);
+//! assert_eq!(seg.query(1..=2, 1..), 24);
+//! ```
+pub mod sparse_segtree2d;
+
+use kyopro_grid::Grid;
+use kyopro_segmenttree::{monoid::Monoid, to_half_open};
+use std::ops::RangeBounds;
+
+/// 2次元 segment tree です
+/// `M` の演算は可換である必要があります
+pub struct SegmentTree2D<M: Monoid> {
+    h: usize,
+    w: usize,
     H: usize,
     W: usize,
-    seg: Vec<T>,
-    // 単位元
-    I: T,
-    f: F,
+    seg: Vec<M::S>,
 }
 
-impl<F: Fn(T, T) -> T, T: Copy + Eq> SegmentTree2D<T, F> {
-    pub fn new(h: usize, w: usize, f: F, I: T) -> SegmentTree2D<T, F> {
+impl<M: Monoid> SegmentTree2D<M> {
+    /// すべての要素が単位元の h x w の segment tree を作成します
+    pub fn new(h: usize, w: usize) -> SegmentTree2D<M> {
         let mut H = 1;
         let mut W = 1;
         while H < h {
@@ -35,41 +48,72 @@ impl<F: Fn(T, T) -> T, T: Copy + Eq> SegmentTree2D<T, F> {
         while W < w {
             W <<= 1;
         }
-        let seg = vec![I; 4 * H * W];
+        let seg = vec![M::identity(); 4 * H * W];
+
+        SegmentTree2D { h, w, H, W, seg }
+    }
 
-        SegmentTree2D { H, W, seg, I, f }
+    /// grid の値で初期化した segment tree を作成します
+    /// O(HW)
+    pub fn from_grid(grid: &Grid<M::S>) -> SegmentTree2D<M> {
+        let h = grid.g.len();
+        let w = grid.g.first().map_or(0, |row| row.len());
+        let mut seg = Self::new(h, w);
+        for (i, row) in grid.g.iter().enumerate() {
+            for (j, x) in row.iter().enumerate() {
+                seg.set(i, j, x.clone());
+            }
+        }
+        seg.build();
+        seg
     }
+
     fn id(&self, h: usize, w: usize) -> usize {
         h * 2 * self.W + w
     }
 
-    pub fn set(&mut self, h: usize, w: usize, x: T) {
+    fn pull_h(&mut self, h: usize, w: usize) {
+        let id1 = self.id(h, w);
+        let id2 = self.id(2 * h, w);
+        let id3 = self.id(2 * h + 1, w);
+        self.seg[id1] = M::binary_operation(&self.seg[id2], &self.seg[id3]);
+    }
+
+    fn pull_w(&mut self, h: usize, w: usize) {
+        let id1 = self.id(h, w);
+        let id2 = self.id(h, 2 * w);
+        let id3 = self.id(h, 2 * w + 1);
+        self.seg[id1] = M::binary_operation(&self.seg[id2], &self.seg[id3]);
+    }
+
+    /// (h, w) の値を x にします
+    /// 反映するには `build` を呼ぶ必要があります
+    pub fn set(&mut self, h: usize, w: usize, x: M::S) {
+        assert!(h < self.h && w < self.w);
         let idx = self.id(h + self.H, w + self.W);
         self.seg[idx] = x;
     }
 
+    /// O(HW)
     pub fn build(&mut self) {
         // w in [W, 2W)
         for w in self.W..2 * self.W {
             for h in (1..self.H).rev() {
-                let id1 = self.id(h, w);
-                let id2 = self.id(2 * h + 0, w);
-                let id3 = self.id(2 * h + 1, w);
-                self.seg[id1] = (self.f)(self.seg[id2], self.seg[id3]);
+                self.pull_h(h, w);
             }
         }
         // h in [0, 2H)
         for h in 0..2 * self.H {
             for w in (1..self.W).rev() {
-                let id1 = self.id(h, w);
-                let id2 = self.id(h, 2 * w + 0);
-                let id3 = self.id(h, 2 * w + 1);
-                self.seg[id1] = (self.f)(self.seg[id2], self.seg[id3]);
+                self.pull_w(h, w);
             }
         }
     }
 
-    pub fn update(&mut self, h: usize, w: usize, x: T) {
+    /// (h, w) の値を x に更新します
+    /// O(log H log W)
+    pub fn update(&mut self, h: usize, w: usize, x: M::S) {
+        assert!(h < self.h && w < self.w);
         let mut h = h + self.H;
         let w = w + self.W;
 
@@ -80,10 +124,7 @@ impl<F: Fn(T, T) -> T, T: Copy + Eq> SegmentTree2D<T, F> {
             // 縦方向の累積
             let mut i = h >> 1;
             while i > 0 {
-                let id1 = self.id(i, w);
-                let id2 = self.id(2 * i + 0, w);
-                let id3 = self.id(2 * i + 1, w);
-                self.seg[id1] = (self.f)(self.seg[id2], self.seg[id3]);
+                self.pull_h(i, w);
                 i >>= 1;
             }
         }
@@ -92,10 +133,7 @@ impl<F: Fn(T, T) -> T, T: Copy + Eq> SegmentTree2D<T, F> {
             while h > 0 {
                 let mut j = w >> 1;
                 while j > 0 {
-                    let id1 = self.id(h, j);
-                    let id2 = self.id(h, 2 * j + 0);
-                    let id3 = self.id(h, 2 * j + 1);
-                    self.seg[id1] = (self.f)(self.seg[id2], self.seg[id3]);
+                    self.pull_w(h, j);
                     j >>= 1;
                 }
                 h >>= 1;
@@ -103,32 +141,33 @@ impl<F: Fn(T, T) -> T, T: Copy + Eq> SegmentTree2D<T, F> {
         }
     }
 
-    fn _inner_query(&self, h: usize, w1: usize, w2: usize) -> T {
-        let mut res: T = self.I;
+    fn _inner_query(&self, h: usize, w1: usize, w2: usize) -> M::S {
+        let mut sml = M::identity();
+        let mut smr = M::identity();
         let mut w1 = w1;
         let mut w2 = w2;
         while w1 < w2 {
             if w1 & 1 > 0 {
-                let id = self.id(h, w1);
-                res = (self.f)(res, self.seg[id]);
+                sml = M::binary_operation(&sml, &self.seg[self.id(h, w1)]);
                 w1 += 1;
             }
             if w2 & 1 > 0 {
                 w2 -= 1;
-                let id = self.id(h, w2);
-                res = (self.f)(res, self.seg[id]);
+                smr = M::binary_operation(&self.seg[self.id(h, w2)], &smr);
             }
             w1 >>= 1;
             w2 >>= 1;
         }
-        res
+        M::binary_operation(&sml, &smr)
     }
 
-    /// [h1, w1] から [h2,w2] (半開区間)  の演算結果を求めます
-    /// 範囲指定順注意 [h1, h2), [w1, w2) の順で指定する
-    pub fn query(&self, h1: usize, h2: usize, w1: usize, w2: usize) -> T {
+    /// 行 `rows`, 列 `cols` の矩形領域の演算結果を求めます
+    /// O(log H log W)
+    pub fn query<R1: RangeBounds<usize>, R2: RangeBounds<usize>>(&self, rows: R1, cols: R2) -> M::S {
+        let (h1, h2) = to_half_open(&rows, self.h);
+        let (w1, w2) = to_half_open(&cols, self.w);
         if h1 >= h2 || w1 >= w2 {
-            return self.I;
+            return M::identity();
         };
 
         let mut h1 = h1 + self.H;
@@ -136,45 +175,101 @@ impl<F: Fn(T, T) -> T, T: Copy + Eq> SegmentTree2D<T, F> {
         let w1 = w1 + self.W;
         let w2 = w2 + self.W;
 
-        let mut res = self.I;
+        let mut sml = M::identity();
+        let mut smr = M::identity();
         while h1 < h2 {
             if h1 & 1 > 0 {
                 let tmp = self._inner_query(h1, w1, w2);
-                res = (self.f)(res, tmp);
+                sml = M::binary_operation(&sml, &tmp);
                 h1 += 1;
             }
             if h2 & 1 > 0 {
                 h2 -= 1;
                 let tmp = self._inner_query(h2, w1, w2);
-                res = (self.f)(res, tmp);
+                smr = M::binary_operation(&tmp, &smr);
             }
 
             h1 >>= 1;
             h2 >>= 1;
         }
-        res
+        M::binary_operation(&sml, &smr)
     }
 
-    pub fn get(&self, h: usize, w: usize) -> T {
-        self.seg[self.id(h + self.H, w + self.W)]
+    pub fn get(&self, h: usize, w: usize) -> M::S {
+        self.seg[self.id(h + self.H, w + self.W)].clone()
     }
 }
 
-#[test]
-fn test_query() {
-    let mut seg = SegmentTree2D::new(3, 3, |a: usize, b: usize| a + b, 0usize);
-    for i in 0..3 {
-        for j in 0..3 {
-            seg.set(i, j, i * 3 + j);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kyopro_segmenttree::monoid::{Max, Sum};
+
+    #[test]
+    fn test_query() {
+        let mut seg: SegmentTree2D<Sum<usize>> = SegmentTree2D::new(3, 3);
+        for i in 0..3 {
+            for j in 0..3 {
+                seg.set(i, j, i * 3 + j);
+            }
+        }
+        seg.build();
+        assert_eq!(seg.query(0..1, 0..1), 0);
+        assert_eq!(seg.query(2..3, 2..3), 8);
+        assert_eq!(seg.query(0..3, 0..3), 36);
+        assert_eq!(seg.query(0..1, 0..3), 3);
+        assert_eq!(seg.query(1..3, 1..3), 24);
+        assert_eq!(seg.query(1.., ..=1), 3 + 4 + 6 + 7);
+        assert_eq!(seg.query(2..2, ..), 0);
+    }
+
+    /// 文字列の連結 (可換でない)
+    struct Concat;
+    impl Monoid for Concat {
+        type S = String;
+        fn identity() -> String {
+            String::new()
+        }
+        fn binary_operation(a: &String, b: &String) -> String {
+            format!("{}{}", a, b)
         }
     }
-    seg.build();
-    assert_eq!(seg.query(0, 1, 0, 1), 0);
-    assert_eq!(seg.query(2, 3, 2, 3), 8);
-    assert_eq!(seg.query(0, 3, 0, 3), 36);
-    assert_eq!(seg.query(0, 1, 0, 3), 3);
-    assert_eq!(seg.query(1, 3, 1, 3), 24);
-}
 
+    #[test]
+    fn test_non_commutative_line() {
+        // 1 行・1 列の矩形は左 (上) から順に演算される
+        let s: Vec<String> = "abcdefg".chars().map(|c| c.to_string()).collect();
+        let row = SegmentTree2D::<Concat>::from_grid(&Grid::from_vec(vec![s.clone()]));
+        let col = SegmentTree2D::<Concat>::from_grid(&Grid::from_vec(s.iter().map(|c| vec![c.clone()]).collect()));
+        for l in 0..=s.len() {
+            for r in l..=s.len() {
+                assert_eq!(row.query(.., l..r), "abcdefg"[l..r]);
+                assert_eq!(col.query(l..r, ..), "abcdefg"[l..r]);
+            }
+        }
+
+        // 一般の矩形では順序が保証されないので、可換なモノイドを使う
+        let g = Grid::from_vec(vec![vec!["a".to_string(), "b".to_string()], vec!["c".to_string(), "d".to_string()]]);
+        let seg = SegmentTree2D::<Concat>::from_grid(&g);
+        let mut got: Vec<char> = seg.query(.., ..).chars().collect();
+        got.sort();
+        assert_eq!(got, vec!['a', 'b', 'c', 'd']);
+    }
 
+    #[test]
+    fn test_from_grid_float() {
+        let g = Grid::from_vec(vec![vec![1.5, -2.0, 0.5], vec![3.0, 0.25, -1.0]]);
+        let mut seg: SegmentTree2D<Max<f64>> = SegmentTree2D::from_grid(&g);
+        assert_eq!(seg.query(.., ..), 3.0);
+        assert_eq!(seg.query(..1, 1..), 0.5);
+        seg.update(1, 0, -5.0);
+        assert_eq!(seg.query(.., ..), 1.5);
+        assert_eq!(seg.query(1.., ..), 0.25);
+        assert_eq!(seg.get(1, 0), -5.0);
 
+        let mut sum: SegmentTree2D<Sum<f64>> = SegmentTree2D::from_grid(&g);
+        assert_eq!(sum.query(.., ..), 2.25);
+        sum.update(0, 0, 0.0);
+        assert_eq!(sum.query(.., ..2), 1.25);
+    }
+}
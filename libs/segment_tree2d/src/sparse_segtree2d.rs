@@ -0,0 +1,242 @@
+//! 点の座標を先読みする 2次元 segment tree
+//!
+//! x 座標を座標圧縮した segment tree の各ノードに、そのノードに含まれる点の y 座標のリストと
+//! その上の segment tree を持たせます。
+//! メモリは O(n log n) (n は登録した点の数) なので、座標が 1e9 程度でも使えます。
+//! `SegmentTree2D` と同じく、演算は可換である必要があります。
+//!
+//! ## Example
+//! ```
+//! use kyopro_segmenttree::monoid::Sum;
+//! use kyopro_segmenttree2d::sparse_segtree2d::SparseSegmentTree2D;
+//! let points = [(1, 1), (1_000_000_000, 5), (3, 1_000_000_000)];
+//! let mut seg: SparseSegmentTree2D<Sum<i64>, i64> = SparseSegmentTree2D::new(&points);
+//! seg.combine(1, 1, 2);
+//! seg.combine(3, 1_000_000_000, 10);
+//! seg.combine(1, 1, 3);
+//! assert_eq!(seg.query(.., ..), 15);
+//! assert_eq!(seg.query(..=3, ..1_000_000_000), 5);
+//! assert_eq!(seg.query(2.., ..), 10);
+//! ```
+use kyopro_segmenttree::{monoid::Monoid, segtree::SegTree};
+use kyopro_zaatsu::Zaatsu;
+use std::ops::{Bound, RangeBounds};
+
+pub struct SparseSegmentTree2D<M: Monoid, T: Ord + Copy> {
+    za: Zaatsu<T>,
+    /// 圧縮後の x 座標
+    xs: Vec<T>,
+    size: usize,
+    /// 各ノードに含まれる点の y 座標 (昇順, 重複なし)
+    ys: Vec<Vec<T>>,
+    segs: Vec<SegTree<M>>,
+}
+
+impl<M: Monoid, T: Ord + Copy> SparseSegmentTree2D<M, T> {
+    /// 値を持つ可能性のある点 `(x, y)` をすべて渡して作成します
+    /// 初期値はすべて単位元です
+    /// O(n log n)
+    pub fn new(points: &[(T, T)]) -> Self {
+        let mut za = Zaatsu::new();
+        for &(x, _) in points {
+            za.add(x);
+        }
+        za.init();
+        let n = za.size();
+        let xs: Vec<T> = (0..n).map(|i| za[i]).collect();
+        let size = n.next_power_of_two();
+        let mut ys = vec![vec![]; 2 * size];
+        for &(x, y) in points {
+            ys[size + za.index(&x)].push(y);
+        }
+        for v in &mut ys[size..] {
+            v.sort();
+            v.dedup();
+        }
+        for k in (1..size).rev() {
+            ys[k] = merge(&ys[2 * k], &ys[2 * k + 1]);
+        }
+        let segs = ys.iter().map(|v| SegTree::new(v.len())).collect();
+        SparseSegmentTree2D { za, xs, size, ys, segs }
+    }
+
+    fn x_index(&self, x: T) -> Option<usize> {
+        if self.xs.is_empty() {
+            return None;
+        }
+        let i = self.za.index(&x);
+        if self.xs[i] == x {
+            Some(i)
+        } else {
+            None
+        }
+    }
+
+    fn value_at(&self, k: usize, y: T) -> M::S {
+        match self.ys[k].binary_search(&y) {
+            Ok(p) => self.segs[k].get(p),
+            Err(_) => M::identity(),
+        }
+    }
+
+    /// 点 (x, y) の値を v にします
+    /// (x, y) は `new` で登録した点である必要があります
+    /// O(log^2 n)
+    pub fn set(&mut self, x: T, y: T, v: M::S) {
+        let i = self.x_index(x).expect("point is not registered");
+        let mut k = i + self.size;
+        let p = self.ys[k].binary_search(&y).expect("point is not registered");
+        self.segs[k].set(p, v);
+        while k > 1 {
+            k >>= 1;
+            let v = M::binary_operation(&self.value_at(2 * k, y), &self.value_at(2 * k + 1, y));
+            let p = self.ys[k].binary_search(&y).unwrap();
+            self.segs[k].set(p, v);
+        }
+    }
+
+    /// 点 (x, y) の値を `op(a[x][y], v)` にします (`Sum` なら加算)
+    /// O(log^2 n)
+    pub fn combine(&mut self, x: T, y: T, v: M::S) {
+        let v = M::binary_operation(&self.get(x, y), &v);
+        self.set(x, y, v);
+    }
+
+    /// 点 (x, y) の値を返します
+    /// 登録されていない点の場合は単位元です
+    /// O(log n)
+    pub fn get(&self, x: T, y: T) -> M::S {
+        match self.x_index(x) {
+            Some(i) => self.value_at(i + self.size, y),
+            None => M::identity(),
+        }
+    }
+
+    /// x 座標が `xr`, y 座標が `yr` に含まれる点の演算結果を返します
+    /// O(log^2 n)
+    pub fn query<R1: RangeBounds<T>, R2: RangeBounds<T>>(&self, xr: R1, yr: R2) -> M::S {
+        let (l, r) = index_range(&self.xs, &xr);
+        let mut sml = M::identity();
+        let mut smr = M::identity();
+        let mut l = l + self.size;
+        let mut r = r + self.size;
+        while l < r {
+            if l & 1 == 1 {
+                sml = M::binary_operation(&sml, &self.inner_query(l, &yr));
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                smr = M::binary_operation(&self.inner_query(r, &yr), &smr);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        M::binary_operation(&sml, &smr)
+    }
+
+    fn inner_query<R: RangeBounds<T>>(&self, k: usize, yr: &R) -> M::S {
+        let (l, r) = index_range(&self.ys[k], yr);
+        self.segs[k].prod(l..r)
+    }
+}
+
+/// 昇順の列 `v` のうち `range` に含まれる要素の添字の半開区間を返します
+fn index_range<T: Ord, R: RangeBounds<T>>(v: &[T], range: &R) -> (usize, usize) {
+    let l = match range.start_bound() {
+        Bound::Included(s) => v.partition_point(|x| x < s),
+        Bound::Excluded(s) => v.partition_point(|x| x <= s),
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(e) => v.partition_point(|x| x <= e),
+        Bound::Excluded(e) => v.partition_point(|x| x < e),
+        Bound::Unbounded => v.len(),
+    };
+    (l, r.max(l))
+}
+
+/// 昇順で重複のない 2つの列をマージします
+fn merge<T: Ord + Copy>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut res = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if j == b.len() || (i < a.len() && a[i] < b[j]) {
+            res.push(a[i]);
+            i += 1;
+        } else {
+            if i < a.len() && a[i] == b[j] {
+                i += 1;
+            }
+            res.push(b[j]);
+            j += 1;
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kyopro_segmenttree::monoid::{Max, Sum};
+    use kyopro_utils::XorShift;
+
+    #[test]
+    fn test_random_sum() {
+        let mut rng = XorShift::new(88172645463325252);
+        let points: Vec<(i64, i64)> = (0..60)
+            .map(|_| (rng.rand(20) as i64 - 5, rng.rand(20) as i64 - 5))
+            .collect();
+        let mut seg: SparseSegmentTree2D<Sum<i64>, i64> = SparseSegmentTree2D::new(&points);
+        let mut naive = std::collections::HashMap::new();
+        for _ in 0..300 {
+            if rng.rand(2) == 0 {
+                let (x, y) = points[rng.rand(points.len() as u64) as usize];
+                let v = rng.rand(100) as i64 - 50;
+                if rng.rand(2) == 0 {
+                    seg.combine(x, y, v);
+                    *naive.entry((x, y)).or_insert(0) += v;
+                } else {
+                    seg.set(x, y, v);
+                    naive.insert((x, y), v);
+                }
+                assert_eq!(seg.get(x, y), naive[&(x, y)]);
+            } else {
+                let x1 = rng.rand(25) as i64 - 7;
+                let x2 = rng.rand(25) as i64 - 7;
+                let y1 = rng.rand(25) as i64 - 7;
+                let y2 = rng.rand(25) as i64 - 7;
+                let expected: i64 = naive
+                    .iter()
+                    .filter(|(&(x, y), _)| x1 <= x && x < x2 && y1 < y && y <= y2)
+                    .map(|(_, &v)| v)
+                    .sum();
+                assert_eq!(seg.query(x1..x2, (Bound::Excluded(y1), Bound::Included(y2))), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_large_coordinates() {
+        let points = [(0u64, 0u64), (1 << 40, 7), (1 << 40, 1 << 50), (5, 1 << 50)];
+        let mut seg: SparseSegmentTree2D<Max<i32>, u64> = SparseSegmentTree2D::new(&points);
+        assert_eq!(seg.query(.., ..), i32::MIN);
+        seg.set(1 << 40, 7, 3);
+        seg.set(5, 1 << 50, 8);
+        seg.set(1 << 40, 1 << 50, 5);
+        assert_eq!(seg.query(.., ..), 8);
+        assert_eq!(seg.query(6.., ..), 5);
+        assert_eq!(seg.query(6.., ..100), 3);
+        assert_eq!(seg.query(..5, ..), i32::MIN);
+        assert_eq!(seg.get(3, 3), i32::MIN);
+        seg.set(5, 1 << 50, 1);
+        assert_eq!(seg.query(..=5, ..), 1);
+    }
+
+    #[test]
+    fn test_empty() {
+        let seg: SparseSegmentTree2D<Sum<i64>, i64> = SparseSegmentTree2D::new(&[]);
+        assert_eq!(seg.query(.., ..), 0);
+        assert_eq!(seg.get(0, 0), 0);
+    }
+}
@@ -0,0 +1,79 @@
+//! disjoint sparse table
+//! 結合則を満たす演算であれば、冪等でなくても O(1) で区間クエリに答えます
+//!
+//! ## Example
+//! ```
+//! use kyopro_sparse_table::disjoint::DisjointSparseTable;
+//! let st = DisjointSparseTable::new(vec![3, 1, 4, 1, 5], |a: &i64, b: &i64| a + b);
+//! assert_eq!(st.query(1, 4), 6);
+//! assert_eq!(st.query(0, 5), 14);
+//! ```
+
+pub struct DisjointSparseTable<T, F> {
+    // table[k]: 長さ 2^(k+1) のブロックの中央から左右に伸ばした演算結果
+    // table[0] は元の列
+    table: Vec<Vec<T>>,
+    f: F,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> DisjointSparseTable<T, F> {
+    /// O(n log n)
+    pub fn new(v: Vec<T>, f: F) -> Self {
+        let n = v.len();
+        let mut table = vec![v.clone()];
+        let mut k = 1;
+        while (1 << k) < n {
+            let half = 1 << k;
+            let mut row = v.clone();
+            for mid in (half..n).step_by(2 * half) {
+                // 左側 [mid - half, mid) は右から、右側 [mid, mid + half) は左から畳み込む
+                for i in (mid - half..mid - 1).rev() {
+                    row[i] = f(&v[i], &row[i + 1]);
+                }
+                for i in mid + 1..n.min(mid + half) {
+                    row[i] = f(&row[i - 1], &v[i]);
+                }
+            }
+            table.push(row);
+            k += 1;
+        }
+        DisjointSparseTable { table, f }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// [l, r) の演算結果を求めます
+    /// O(1)
+    pub fn query(&self, l: usize, r: usize) -> T {
+        assert!(l < r && r <= self.len());
+        let r = r - 1;
+        if l == r {
+            return self.table[0][l].clone();
+        }
+        let k = (l ^ r).ilog2() as usize;
+        (self.f)(&self.table[k][l], &self.table[k][r])
+    }
+}
+
+#[test]
+fn test_disjoint_sparse_table() {
+    for n in 1..20 {
+        let v: Vec<i64> = (0..n).map(|i| (i * 37 % 11) as i64 - 5).collect();
+        let sum = DisjointSparseTable::new(v.clone(), |a: &i64, b: &i64| a + b);
+        // 非可換な演算 (文字列の連結)
+        let s: Vec<String> = (0..n).map(|i| ((b'a' + i as u8) as char).to_string()).collect();
+        let concat = DisjointSparseTable::new(s.clone(), |a: &String, b: &String| format!("{}{}", a, b));
+        for l in 0..n {
+            for r in l + 1..=n {
+                assert_eq!(sum.query(l, r), v[l..r].iter().sum::<i64>());
+                assert_eq!(concat.query(l, r), s[l..r].concat());
+            }
+        }
+    }
+}
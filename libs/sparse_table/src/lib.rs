@@ -0,0 +1,82 @@
+//! sparse table です。静的な列の区間クエリに O(1) で答えます
+//!
+//! - `SparseTable`: min, max, gcd など冪等な演算 (`f(x, x) = x`)
+//! - `disjoint::DisjointSparseTable`: 結合則を満たす任意の演算
+//!
+//! 区間は半開区間 `[l, r)` で指定し、空であってはいけません
+//!
+//! ## Example
+//! ```
+//! use kyopro_sparse_table::SparseTable;
+//! let st = SparseTable::new(vec![5, 3, 7, 1, 6], |a: &i32, b: &i32| *a.min(b));
+//! assert_eq!(st.query(0, 3), 3);
+//! assert_eq!(st.query(2, 5), 1);
+//! ```
+pub mod disjoint;
+
+pub struct SparseTable<T, F> {
+    // table[k][i]: [i, i + 2^k) の演算結果
+    table: Vec<Vec<T>>,
+    f: F,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> SparseTable<T, F> {
+    /// O(n log n)
+    pub fn new(v: Vec<T>, f: F) -> Self {
+        let n = v.len();
+        let mut table = vec![v];
+        let mut k = 1;
+        while (1 << k) <= n {
+            let prev = &table[k - 1];
+            let half = 1 << (k - 1);
+            let row = (0..=n - (1 << k)).map(|i| f(&prev[i], &prev[i + half])).collect();
+            table.push(row);
+            k += 1;
+        }
+        SparseTable { table, f }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// [l, r) の演算結果を求めます
+    /// O(1)
+    pub fn query(&self, l: usize, r: usize) -> T {
+        assert!(l < r && r <= self.len());
+        let k = (r - l).ilog2() as usize;
+        (self.f)(&self.table[k][l], &self.table[k][r - (1 << k)])
+    }
+}
+
+#[test]
+fn test_sparse_table() {
+    let v = vec![4u64, 6, 12, 9, 3, 3, 8, 18, 27, 5, 10];
+    let min = SparseTable::new(v.clone(), |a: &u64, b: &u64| *a.min(b));
+    let max = SparseTable::new(v.clone(), |a: &u64, b: &u64| *a.max(b));
+    let gcd = SparseTable::new(v.clone(), |a: &u64, b: &u64| {
+        let (mut a, mut b) = (*a, *b);
+        while b > 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    });
+    for l in 0..v.len() {
+        for r in l + 1..=v.len() {
+            assert_eq!(min.query(l, r), *v[l..r].iter().min().unwrap());
+            assert_eq!(max.query(l, r), *v[l..r].iter().max().unwrap());
+            let g = v[l..r].iter().fold(0, |g, &x| {
+                let (mut a, mut b) = (g, x);
+                while b > 0 {
+                    (a, b) = (b, a % b);
+                }
+                a
+            });
+            assert_eq!(gcd.query(l, r), g);
+        }
+    }
+}
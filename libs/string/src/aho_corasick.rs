@@ -0,0 +1,233 @@
+//! Aho-Corasick 法
+//! 複数パターンの文字列検索を行うオートマトンです
+//!
+//! 文字は `0..sigma` の整数で表します。
+//! 文字列のパターンは `add_str` で `first` を 0 とした整数列に変換して追加できます。
+//!
+//! ## Example
+//! ```
+//! use kyopro_string::aho_corasick::AhoCorasickBuilder;
+//! let mut builder = AhoCorasickBuilder::new(26);
+//! builder.add_str("he", b'a');
+//! builder.add_str("she", b'a');
+//! builder.add_str("hers", b'a');
+//! let ac = builder.build();
+//!
+//! let text: Vec<usize> = "ushers".bytes().map(|c| (c - b'a') as usize).collect();
+//! // "she", "he", "hers" が 1 回ずつ出現する
+//! assert_eq!(ac.count_all(&text), 3);
+//! assert_eq!(ac.count_each(&text), vec![1, 1, 1]);
+//! ```
+use std::collections::VecDeque;
+
+/// 遷移先が存在しないことを表す値
+const NONE: usize = !0;
+
+/// パターンを登録して [`AhoCorasick`] を構築します
+#[derive(Clone, Debug)]
+pub struct AhoCorasickBuilder {
+    sigma: usize,
+    goto: Vec<Vec<usize>>,
+    /// 各パターンの終端ノード
+    terminals: Vec<usize>,
+}
+
+impl AhoCorasickBuilder {
+    /// + `sigma`: 文字種数
+    pub fn new(sigma: usize) -> Self {
+        AhoCorasickBuilder { sigma, goto: vec![vec![NONE; sigma]], terminals: vec![] }
+    }
+
+    /// パターンを追加し、パターン番号を返します
+    pub fn add(&mut self, pattern: &[usize]) -> usize {
+        let mut v = 0;
+        for &c in pattern {
+            assert!(c < self.sigma);
+            if self.goto[v][c] == NONE {
+                self.goto[v][c] = self.goto.len();
+                self.goto.push(vec![NONE; self.sigma]);
+            }
+            v = self.goto[v][c];
+        }
+        self.terminals.push(v);
+        self.terminals.len() - 1
+    }
+
+    /// 文字列のパターンを追加し、パターン番号を返します
+    /// + `first`: 文字 `0` として扱う文字 (`b'a'` など)
+    pub fn add_str(&mut self, pattern: &str, first: u8) -> usize {
+        let p: Vec<usize> = pattern.bytes().map(|c| (c - first) as usize).collect();
+        self.add(&p)
+    }
+
+    /// オートマトンを構築します
+    /// O(状態数 * sigma)
+    pub fn build(self) -> AhoCorasick {
+        let AhoCorasickBuilder { mut goto, terminals, .. } = self;
+        let n = goto.len();
+        let mut fail = vec![0; n];
+        let mut count = vec![0; n];
+        for &t in &terminals {
+            count[t] += 1;
+        }
+
+        // BFS 順に fail を求め、存在しない遷移を fail 先の遷移で埋める
+        let mut order = Vec::with_capacity(n);
+        let mut que = VecDeque::new();
+        for u in goto[0].iter_mut() {
+            if *u == NONE {
+                *u = 0;
+            } else {
+                que.push_back(*u);
+            }
+        }
+        while let Some(v) = que.pop_front() {
+            order.push(v);
+            count[v] += count[fail[v]];
+            // fail[v] は v より浅いので遷移は確定済み
+            let fail_goto = goto[fail[v]].clone();
+            for (u, &f) in goto[v].iter_mut().zip(&fail_goto) {
+                if *u == NONE {
+                    *u = f;
+                } else {
+                    fail[*u] = f;
+                    que.push_back(*u);
+                }
+            }
+        }
+        AhoCorasick { goto, fail, count, terminals, order }
+    }
+}
+
+/// Aho-Corasick オートマトン
+///
+/// 状態 0 が初期状態 (空文字列) です。
+/// DP に使う場合は `transitions` で遷移表を、`count` で各状態で受理されるパターン数を参照します。
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+    goto: Vec<Vec<usize>>,
+    fail: Vec<usize>,
+    /// 状態で終わるパターン数 (suffix で一致するものも含む)
+    count: Vec<usize>,
+    terminals: Vec<usize>,
+    /// 初期状態を除く状態の BFS 順
+    order: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// 状態数を返します
+    pub fn len(&self) -> usize {
+        self.goto.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.goto.is_empty()
+    }
+
+    /// 状態 `v` から文字 `c` で遷移した先の状態を返します
+    #[inline]
+    pub fn next(&self, v: usize, c: usize) -> usize {
+        self.goto[v][c]
+    }
+
+    /// 遷移表を返します
+    /// `transitions()[v][c]` が状態 `v` から文字 `c` での遷移先です
+    pub fn transitions(&self) -> &[Vec<usize>] {
+        &self.goto
+    }
+
+    /// 状態 `v` の fail 先を返します
+    pub fn fail(&self, v: usize) -> usize {
+        self.fail[v]
+    }
+
+    /// 状態 `v` に到達したときに末尾で一致しているパターン数を返します
+    pub fn count(&self, v: usize) -> usize {
+        self.count[v]
+    }
+
+    /// パターン `id` の終端状態を返します
+    pub fn terminal(&self, id: usize) -> usize {
+        self.terminals[id]
+    }
+
+    /// `text` に含まれるパターンの出現回数の合計を返します
+    /// O(|text|)
+    pub fn count_all(&self, text: &[usize]) -> usize {
+        let mut v = 0;
+        let mut res = 0;
+        for &c in text {
+            v = self.next(v, c);
+            res += self.count[v];
+        }
+        res
+    }
+
+    /// `text` に含まれる各パターンの出現回数を返します
+    /// O(|text| + 状態数)
+    pub fn count_each(&self, text: &[usize]) -> Vec<usize> {
+        let mut visit = vec![0; self.len()];
+        let mut v = 0;
+        for &c in text {
+            v = self.next(v, c);
+            visit[v] += 1;
+        }
+        // fail 木の葉から根に向かって訪問回数を集約する
+        for &v in self.order.iter().rev() {
+            visit[self.fail[v]] += visit[v];
+        }
+        self.terminals.iter().map(|&t| visit[t]).collect()
+    }
+}
+
+#[test]
+fn test_count() {
+    let patterns = ["a", "ab", "bab", "b", "aa"];
+    let text = "abababaab";
+    let mut builder = AhoCorasickBuilder::new(2);
+    for p in &patterns {
+        builder.add_str(p, b'a');
+    }
+    let ac = builder.build();
+    let t: Vec<usize> = text.bytes().map(|c| (c - b'a') as usize).collect();
+
+    let expected: Vec<usize> = patterns
+        .iter()
+        .map(|p| (0..=text.len() - p.len()).filter(|&i| text[i..].starts_with(p)).count())
+        .collect();
+    assert_eq!(ac.count_each(&t), expected);
+    assert_eq!(ac.count_all(&t), expected.iter().sum::<usize>());
+}
+
+#[test]
+fn test_duplicate_pattern() {
+    let mut builder = AhoCorasickBuilder::new(3);
+    let a = builder.add(&[0, 1]);
+    let b = builder.add(&[0, 1]);
+    let ac = builder.build();
+    assert_eq!(ac.terminal(a), ac.terminal(b));
+    assert_eq!(ac.count_each(&[0, 1, 2, 0, 1]), vec![2, 2]);
+    assert_eq!(ac.count_all(&[0, 1, 2, 0, 1]), 4);
+}
+
+#[test]
+fn test_dp_table() {
+    // 長さ 4 の {0, 1} 列のうち "11" を含まないものの個数
+    let mut builder = AhoCorasickBuilder::new(2);
+    builder.add(&[1, 1]);
+    let ac = builder.build();
+    let mut dp = vec![0usize; ac.len()];
+    dp[0] = 1;
+    for _ in 0..4 {
+        let mut nx = vec![0; ac.len()];
+        for (v, &x) in dp.iter().enumerate() {
+            for &u in &ac.transitions()[v] {
+                if ac.count(u) == 0 {
+                    nx[u] += x;
+                }
+            }
+        }
+        dp = nx;
+    }
+    assert_eq!(dp.iter().sum::<usize>(), 8);
+}
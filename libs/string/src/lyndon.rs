@@ -0,0 +1,121 @@
+//! Lyndon 分解と最小回転
+//!
+//! 巡回列 (ネックレス) の正規化に使います。
+//! グリッドを `kyopro_grid::rot_clock` で回転させて正規化するのと同様に、
+//! 列を最小回転に揃えることで回転同一視ができます。
+//!
+//! ## Example
+//! ```
+//! use kyopro_string::lyndon::{lyndon_factorization, min_rotation};
+//! // "b" "ab" "aab" に分解される
+//! assert_eq!(lyndon_factorization(b"babaab"), vec![(0, 1), (1, 3), (3, 6)]);
+//! // "cab", "abc", "bca" はすべて "abc" に正規化される
+//! assert_eq!(min_rotation(b"cab"), b"abc".to_vec());
+//! assert_eq!(min_rotation(b"bca"), b"abc".to_vec());
+//! ```
+
+/// Duval のアルゴリズムで Lyndon 分解を求めます
+/// 各 Lyndon 語を半開区間 `[l, r)` で返します (辞書順で非増加に並びます)
+/// O(|s|)
+pub fn lyndon_factorization<T: Ord>(s: &[T]) -> Vec<(usize, usize)> {
+    let n = s.len();
+    let mut res = vec![];
+    let mut i = 0;
+    while i < n {
+        let mut j = i + 1;
+        let mut k = i;
+        while j < n && s[k] <= s[j] {
+            if s[k] < s[j] {
+                k = i;
+            } else {
+                k += 1;
+            }
+            j += 1;
+        }
+        // 長さ j - k の Lyndon 語が繰り返されている
+        while i <= k {
+            res.push((i, i + j - k));
+            i += j - k;
+        }
+    }
+    res
+}
+
+/// Booth のアルゴリズムで辞書順最小の巡回回転の開始位置を返します
+/// 最小回転が複数の位置から得られる場合はそのうちのいずれかを返します
+/// O(|s|)
+pub fn min_rotation_index<T: Ord>(s: &[T]) -> usize {
+    let n = s.len();
+    if n == 0 {
+        return 0;
+    }
+    // f: s + s 上の failure function (-1 は border なし)
+    let mut f = vec![-1isize; 2 * n];
+    let mut k = 0;
+    for j in 1..2 * n {
+        let c = &s[j % n];
+        let mut i = f[j - k - 1];
+        while i != -1 && *c != s[(k + i as usize + 1) % n] {
+            if *c < s[(k + i as usize + 1) % n] {
+                k = j - i as usize - 1;
+            }
+            i = f[i as usize];
+        }
+        if i == -1 && *c != s[k % n] {
+            if *c < s[k % n] {
+                k = j;
+            }
+            f[j - k] = -1;
+        } else {
+            f[j - k] = i + 1;
+        }
+    }
+    k % n
+}
+
+/// 辞書順最小の巡回回転を返します
+/// O(|s|)
+pub fn min_rotation<T: Ord + Clone>(s: &[T]) -> Vec<T> {
+    let k = min_rotation_index(s);
+    s[k..].iter().chain(&s[..k]).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_lyndon(s: &[u8]) -> bool {
+        // 真の接尾辞すべてより辞書順で小さい
+        !s.is_empty() && (1..s.len()).all(|i| s < &s[i..])
+    }
+
+    fn naive_min_rotation(s: &[u8]) -> Vec<u8> {
+        (0..s.len())
+            .map(|k| s[k..].iter().chain(&s[..k]).copied().collect::<Vec<_>>())
+            .min()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_lyndon_factorization() {
+        for s in [&b"babaab"[..], b"aaaa", b"abcabcab", b"zyxzyx", b"abab", b"a", b"", b"aabaabab"] {
+            let f = lyndon_factorization(s);
+            // 区間が列全体を覆い、各区間が Lyndon 語で辞書順非増加
+            assert_eq!(f.iter().map(|&(l, r)| r - l).sum::<usize>(), s.len());
+            for w in f.windows(2) {
+                assert_eq!(w[0].1, w[1].0);
+                assert!(s[w[0].0..w[0].1] >= s[w[1].0..w[1].1]);
+            }
+            for &(l, r) in &f {
+                assert!(is_lyndon(&s[l..r]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_rotation() {
+        for s in [&b"cab"[..], b"bbaab", b"aaaa", b"abab", b"baba", b"a", b"", b"dcbadcba", b"acbacbab"] {
+            assert_eq!(min_rotation(s), naive_min_rotation(s));
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! 回文木 (eertree)
+//! 文字列に含まれる相異なる回文を管理します
+//!
+//! 文字は `0..sigma` の整数で表します。
+//! ノード 0 は長さ -1 の仮想的な根、ノード 1 は長さ 0 の根で、2 以降が回文に対応します。
+//!
+//! ## Example
+//! ```
+//! use kyopro_string::palindromic_tree::PalindromicTree;
+//! let s: Vec<usize> = "abaaba".bytes().map(|c| (c - b'a') as usize).collect();
+//! let mut pt = PalindromicTree::new(26);
+//! for &c in &s {
+//!     pt.push(c);
+//! }
+//! // a, b, aa, aba, baab, abaaba
+//! assert_eq!(pt.distinct_palindromes(), 6);
+//!
+//! // "aba" の出現回数
+//! let cnt = pt.occurrence_counts();
+//! let v = (2..pt.len()).find(|&v| pt.palindrome(v) == vec![0, 1, 0]).unwrap();
+//! assert_eq!(cnt[v], 2);
+//! ```
+
+/// 遷移先が存在しないことを表す値
+const NONE: usize = !0;
+
+#[derive(Clone, Debug)]
+pub struct PalindromicTree {
+    sigma: usize,
+    s: Vec<usize>,
+    next: Vec<Vec<usize>>,
+    /// 最長の真の回文 suffix のノード
+    link: Vec<usize>,
+    /// 回文の長さ (ノード 0 は -1)
+    len: Vec<isize>,
+    /// 最長の回文 suffix として現れた回数
+    cnt: Vec<usize>,
+    /// 最初に現れた位置の終端 (半開区間)
+    end: Vec<usize>,
+    /// 現在の文字列の最長の回文 suffix のノード
+    last: usize,
+}
+
+impl PalindromicTree {
+    /// + `sigma`: 文字種数
+    pub fn new(sigma: usize) -> Self {
+        PalindromicTree {
+            sigma,
+            s: vec![],
+            next: vec![vec![NONE; sigma]; 2],
+            link: vec![0, 0],
+            len: vec![-1, 0],
+            cnt: vec![0, 0],
+            end: vec![0, 0],
+            last: 1,
+        }
+    }
+
+    /// `v` から suffix link をたどり、直前に `s[i]` と同じ文字を持つノードを探します
+    fn find(&self, mut v: usize, i: usize) -> usize {
+        loop {
+            let l = self.len[v];
+            if i as isize - 1 - l >= 0 && self.s[(i as isize - 1 - l) as usize] == self.s[i] {
+                return v;
+            }
+            v = self.link[v];
+        }
+    }
+
+    /// 末尾に文字 `c` を追加し、最長の回文 suffix のノードを返します
+    /// 償却 O(1)
+    pub fn push(&mut self, c: usize) -> usize {
+        assert!(c < self.sigma);
+        let i = self.s.len();
+        self.s.push(c);
+        let p = self.find(self.last, i);
+        if self.next[p][c] == NONE {
+            let v = self.next.len();
+            let link = if self.len[p] == -1 {
+                1
+            } else {
+                let q = self.find(self.link[p], i);
+                self.next[q][c]
+            };
+            self.next.push(vec![NONE; self.sigma]);
+            self.link.push(link);
+            self.len.push(self.len[p] + 2);
+            self.cnt.push(0);
+            self.end.push(i + 1);
+            self.next[p][c] = v;
+        }
+        self.last = self.next[p][c];
+        self.cnt[self.last] += 1;
+        self.last
+    }
+
+    /// ノード数 (根 2 つを含む) を返します
+    pub fn len(&self) -> usize {
+        self.next.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.next.is_empty()
+    }
+
+    /// 相異なる空でない回文の個数を返します
+    pub fn distinct_palindromes(&self) -> usize {
+        self.len() - 2
+    }
+
+    /// ノード `v` の回文の長さを返します
+    /// ノード 0 は -1 です
+    pub fn palindrome_len(&self, v: usize) -> isize {
+        self.len[v]
+    }
+
+    /// ノード `v` の最長の真の回文 suffix のノードを返します
+    pub fn link(&self, v: usize) -> usize {
+        self.link[v]
+    }
+
+    /// ノード `v` の両端に文字 `c` を付けた回文のノードを返します
+    pub fn next(&self, v: usize, c: usize) -> Option<usize> {
+        let u = self.next[v][c];
+        if u == NONE {
+            None
+        } else {
+            Some(u)
+        }
+    }
+
+    /// ノード `v` の回文を返します
+    /// O(回文の長さ)
+    pub fn palindrome(&self, v: usize) -> Vec<usize> {
+        if v < 2 {
+            return vec![];
+        }
+        let end = self.end[v];
+        self.s[end - self.len[v] as usize..end].to_vec()
+    }
+
+    /// 各ノードの回文の出現回数を返します
+    /// O(ノード数)
+    pub fn occurrence_counts(&self) -> Vec<usize> {
+        let mut res = self.cnt.clone();
+        // 子ノードは親より後に作られるので、作成順の逆に集約する
+        for v in (2..self.len()).rev() {
+            res[self.link[v]] += res[v];
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PalindromicTree;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_naive() {
+        let s = [0, 1, 0, 0, 1, 0, 1, 1, 0, 2, 0, 1, 1];
+        let mut pt = PalindromicTree::new(3);
+        for &c in &s {
+            pt.push(c);
+        }
+        let mut occ = HashMap::new();
+        for l in 0..s.len() {
+            for r in l + 1..=s.len() {
+                let t = &s[l..r];
+                if t.iter().eq(t.iter().rev()) {
+                    *occ.entry(t.to_vec()).or_insert(0) += 1;
+                }
+            }
+        }
+        assert_eq!(pt.distinct_palindromes(), occ.len());
+        let cnt = pt.occurrence_counts();
+        for (v, &c) in cnt.iter().enumerate().skip(2) {
+            let p = pt.palindrome(v);
+            assert_eq!(p.len() as isize, pt.palindrome_len(v));
+            assert_eq!(occ[&p], c);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! prefix function (KMP の failure function) と、それを使った周期・border の計算です
+//!
+//! ## Example
+//! ```
+//! use kyopro_string::prefix_function::{borders, prefix_function, smallest_period};
+//! let s = b"abcabcab";
+//! assert_eq!(prefix_function(s), vec![0, 0, 0, 1, 2, 3, 4, 5]);
+//! // "abcab", "ab" が border
+//! assert_eq!(borders(s), vec![5, 2]);
+//! assert_eq!(smallest_period(s), 3);
+//! ```
+
+/// prefix function を求めます
+/// 戻り値の `i` 番目は `s[..=i]` の最長の真の border (接頭辞かつ接尾辞) の長さです
+/// O(|s|)
+pub fn prefix_function<T: PartialEq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut pi = vec![0; n];
+    for i in 1..n {
+        let mut k = pi[i - 1];
+        while k > 0 && s[i] != s[k] {
+            k = pi[k - 1];
+        }
+        if s[i] == s[k] {
+            k += 1;
+        }
+        pi[i] = k;
+    }
+    pi
+}
+
+/// `s` の空でない真の border の長さを降順に返します
+/// O(|s|)
+pub fn borders<T: PartialEq>(s: &[T]) -> Vec<usize> {
+    let pi = prefix_function(s);
+    let mut res = vec![];
+    let mut k = pi.last().copied().unwrap_or(0);
+    while k > 0 {
+        res.push(k);
+        k = pi[k - 1];
+    }
+    res
+}
+
+/// `s` の最小周期 (`s[i] == s[i + p]` がすべての i で成り立つ最小の p) を返します
+/// 空列の場合は 0 です
+/// O(|s|)
+pub fn smallest_period<T: PartialEq>(s: &[T]) -> usize {
+    s.len() - prefix_function(s).last().copied().unwrap_or(0)
+}
+
+/// `s` が `s[..p]` の繰り返しで表せる最小の p を返します
+/// 繰り返しで表せない場合は `|s|` です
+/// O(|s|)
+pub fn smallest_full_period<T: PartialEq>(s: &[T]) -> usize {
+    let n = s.len();
+    let p = smallest_period(s);
+    if p > 0 && n % p == 0 {
+        p
+    } else {
+        n
+    }
+}
+
+#[test]
+fn test_prefix_function() {
+    let s = [1, 2, 1, 1, 2, 1, 2, 1, 1, 2];
+    let pi = prefix_function(&s);
+    for i in 0..s.len() {
+        let naive = (0..=i).rev().find(|&k| k <= i && s[..k] == s[i + 1 - k..=i]).unwrap();
+        assert_eq!(pi[i], naive);
+    }
+    assert_eq!(prefix_function::<u8>(&[]), vec![]);
+}
+
+#[test]
+fn test_period() {
+    for s in [&b"abab"[..], b"ababa", b"aaaa", b"abc", b"a", b"", b"abaab"] {
+        let n = s.len();
+        let naive = (1..=n).find(|&p| (0..n - p).all(|i| s[i] == s[i + p])).unwrap_or(0);
+        assert_eq!(smallest_period(s), naive);
+        let naive_borders: Vec<usize> = (1..n).rev().filter(|&k| s[..k] == s[n - k..]).collect();
+        assert_eq!(borders(s), naive_borders);
+    }
+    assert_eq!(smallest_full_period(b"ababab"), 2);
+    assert_eq!(smallest_full_period(b"ababa"), 5);
+    assert_eq!(smallest_full_period(b""), 0);
+}
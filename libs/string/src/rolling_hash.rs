@@ -0,0 +1,689 @@
+//! rolling hash
+//! 以下記事の実装
+//! https://qiita.com/keymoon/items/11fac5627672a6d6a9f6
+#![allow(non_snake_case)]
+use kyopro_grid::{Grid, P};
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const MASK30: u64 = (1u64 << 30) - 1;
+const MASK31: u64 = (1u64 << 31) - 1;
+const MOD: u64 = (1u64 << 61) - 1;
+const MASK61: u64 = MOD;
+const POSITIVIZER: u64 = MOD * 4;
+
+/// rolling hashの基数
+pub const B: u64 = 1_000_000_000 + 7;
+
+/// 2次元 rolling hash の行方向の基数
+pub const B_ROW: u64 = 1_000_000_000 + 9;
+
+/// double hash で `2^61-1` と組み合わせる法
+pub const MOD2: u64 = 998_244_353;
+
+thread_local! {
+    /// B の累乗を保持しておく
+    /// 必要になった分だけ伸長します
+    static BS: RefCell<Vec<u64>> = RefCell::new(vec![1]);
+    /// B_ROW の累乗を保持しておく
+    static ROW_BS: RefCell<Vec<u64>> = RefCell::new(vec![1]);
+}
+/// キャッシュ `bs` を伸長して `b^e` を返します
+fn cached_pow(bs: &RefCell<Vec<u64>>, b: u64, e: usize) -> u64 {
+    let mut bs = bs.borrow_mut();
+    while bs.len() <= e {
+        let v = MulMod(*bs.last().unwrap(), b);
+        bs.push(v);
+    }
+    bs[e]
+}
+/// `B^e` を返します
+/// 償却 O(1)
+fn get_bs(e: usize) -> u64 {
+    BS.with(|bs| cached_pow(bs, B, e))
+}
+/// `B_ROW^e` を返します
+/// 償却 O(1)
+fn get_row_bs(e: usize) -> u64 {
+    ROW_BS.with(|bs| cached_pow(bs, B_ROW, e))
+}
+
+/// `mod 2^61-1` を計算する関数
+pub fn CalcMod(x: u64) -> u64 {
+    let xu = x >> 61;
+    let xd = x & MASK61;
+    let mut res = xu + xd;
+    if res >= MOD {
+        res -= MOD;
+    }
+    res
+}
+
+/// `a*b` を返す関数(最後にModを取らない)
+pub fn Mul(a: u64, b: u64) -> u64 {
+    let au = a >> 31;
+    let ad = a & MASK31;
+    let bu = b >> 31;
+    let bd = b & MASK31;
+    let mid = ad * bu + au * bd;
+    let midu = mid >> 30;
+    let midd = mid & MASK30;
+    au * bu * 2 + midu + (midd << 31) + ad * bd
+}
+
+/// `a*b (mod 2^61-1)` を返す関数
+pub fn MulMod(a: u64, b: u64) -> u64 {
+    CalcMod(Mul(a, b))
+}
+/// `v^e (mod 2^61-1)` を計算します
+pub fn CalcPow(v: u64, e: usize) -> u64 {
+    if e == 0 {
+        return 1;
+    }
+    let mut x = v;
+    let mut res = 1;
+    let mut i = 0;
+    loop {
+        if e >> i == 0 {
+            break;
+        }
+        if e >> i & 1 == 1 {
+            res = CalcMod(Mul(res, x));
+        }
+        x = CalcMod(Mul(x, x));
+        i += 1;
+    }
+    res
+}
+
+/// 数列 `s` のハッシュを取得します
+pub fn hash(s: &[u64]) -> u64 {
+    s.iter().fold(0u64, |s, &v| CalcMod(Mul(s, B) + v))
+}
+
+/// 末尾に `v` を追加したハッシュを取得します
+/// + `h`: 現在の数列の hash
+pub fn push_back(h: u64, v: u64) -> u64 {
+    CalcMod(Mul(h, B) + v)
+}
+
+/// 先頭に `v` を追加したハッシュを取得します
+/// + `h`: 現在の数列の hash
+/// + `current_len`: 現在の数列の 長さ
+///
+/// 計算量: 償却 O(1)
+pub fn push_front(h: u64, current_len: usize, v: u64) -> u64 {
+    let b = get_bs(current_len);
+    CalcMod(h + Mul(v, b))
+}
+
+/// a は b に含まれているか? を返します。
+/// O(|a|+|b|)
+pub fn contains(a: &[u64], b: &[u64]) -> bool {
+    let al = a.len();
+    let bl = b.len();
+    if al > bl {
+        return false;
+    }
+    let ah = hash(a);
+    let mut bh = hash(&b[0..a.len()]);
+    let fa = get_bs(al);
+    for i in 0..bl {
+        if ah == bh {
+            return true;
+        }
+        if i + al >= bl {
+            break;
+        }
+        let d = POSITIVIZER - Mul(b[i], fa);
+
+        bh = CalcMod(CalcMod(Mul(bh, B) + b[i + al]) + d);
+    }
+    false
+}
+/// `lhs - rhs` を計算します
+pub fn Sub(lhs: u64, rhs: u64) -> u64 {
+    let d = POSITIVIZER - rhs;
+    CalcMod(lhs + d)
+}
+
+/// ハッシュ対象の数列に変換できる型です
+/// `&str`, `&[u8]`, `&[char]` など `Into<u64>` な要素の列をそのままハッシュできるようにします
+/// 文字列は `&[char]` と一致するように、バイト列ではなくコードポイントの列としてハッシュします
+pub trait ToHashSeq {
+    fn to_hash_seq(&self) -> Vec<u64>;
+}
+impl ToHashSeq for str {
+    fn to_hash_seq(&self) -> Vec<u64> {
+        self.chars().map(u64::from).collect()
+    }
+}
+impl ToHashSeq for String {
+    fn to_hash_seq(&self) -> Vec<u64> {
+        self.as_str().to_hash_seq()
+    }
+}
+impl<T: Into<u64> + Copy> ToHashSeq for [T] {
+    fn to_hash_seq(&self) -> Vec<u64> {
+        self.iter().map(|&v| v.into()).collect()
+    }
+}
+impl<T: Into<u64> + Copy> ToHashSeq for Vec<T> {
+    fn to_hash_seq(&self) -> Vec<u64> {
+        self.as_slice().to_hash_seq()
+    }
+}
+
+/// 文字列や `Into<u64>` な要素の列のハッシュを取得します
+/// ```
+/// use kyopro_string::rolling_hash::{hash, hash_of};
+/// let chars: Vec<char> = "abc".chars().collect();
+/// assert_eq!(hash_of("abc"), hash_of(&chars));
+/// assert_eq!(hash_of("abc"), hash_of(&[b'a', b'b', b'c'][..]));
+/// assert_eq!(hash_of("abc"), hash(&[97, 98, 99]));
+/// ```
+pub fn hash_of<S: ToHashSeq + ?Sized>(s: &S) -> u64 {
+    hash(&s.to_hash_seq())
+}
+
+/// a は b に含まれているか? を返します。
+/// `contains` の文字列などを受け付ける版です
+pub fn contains_of<S: ToHashSeq + ?Sized, T: ToHashSeq + ?Sized>(a: &S, b: &T) -> bool {
+    contains(&a.to_hash_seq(), &b.to_hash_seq())
+}
+
+/// 数列 `s` の後ろに長さ `len_t` の数列 `t` を連結したハッシュを取得します
+/// + `hs`: `s` の hash
+/// + `ht`: `t` の hash
+pub fn concat(hs: u64, ht: u64, len_t: usize) -> u64 {
+    CalcMod(Mul(hs, get_bs(len_t)) + ht)
+}
+
+/// 数列の累積ハッシュを前計算し、連続部分列のハッシュを O(1) で求めます
+///
+/// ## Example
+/// ```
+/// use kyopro_string::rolling_hash::{hash, RollingHash};
+/// let s = [1, 2, 3, 1, 2, 4];
+/// let rh = RollingHash::new(&s);
+/// assert_eq!(rh.substring_hash(0, 2), rh.substring_hash(3, 5));
+/// assert_eq!(rh.substring_hash(1, 4), hash(&[2, 3, 1]));
+/// // s[0..] と s[3..] の最長共通接頭辞は [1, 2]
+/// assert_eq!(rh.lcp(0, 3), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RollingHash {
+    s: Vec<u64>,
+    /// `hs[i]`: `s[0..i]` のハッシュ
+    hs: Vec<u64>,
+    /// `pw[i]`: `B^i`
+    pw: Vec<u64>,
+}
+
+impl RollingHash {
+    /// O(|s|) で前計算します
+    pub fn new(s: &[u64]) -> Self {
+        let n = s.len();
+        let mut hs = vec![0; n + 1];
+        let mut pw = vec![1; n + 1];
+        for i in 0..n {
+            hs[i + 1] = push_back(hs[i], s[i]);
+            pw[i + 1] = MulMod(pw[i], B);
+        }
+        RollingHash { s: s.to_vec(), hs, pw }
+    }
+
+    /// 文字列などから構築します
+    /// ```
+    /// use kyopro_string::rolling_hash::RollingHash;
+    /// let rh = RollingHash::from_seq("abcab");
+    /// assert_eq!(rh.lcp(0, 3), 2);
+    /// ```
+    pub fn from_seq<S: ToHashSeq + ?Sized>(s: &S) -> Self {
+        Self::new(&s.to_hash_seq())
+    }
+
+    /// 数列の長さを返します
+    pub fn len(&self) -> usize {
+        self.s.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.s.is_empty()
+    }
+
+    /// 区間 `[l, r)` のハッシュを返します
+    /// O(1)
+    pub fn substring_hash(&self, l: usize, r: usize) -> u64 {
+        assert!(l <= r && r <= self.len());
+        Sub(self.hs[r], MulMod(self.hs[l], self.pw[r - l]))
+    }
+
+    /// `s[i..]` と `s[j..]` の最長共通接頭辞の長さを返します
+    /// O(log(|s|))
+    pub fn lcp(&self, i: usize, j: usize) -> usize {
+        let n = self.len();
+        assert!(i <= n && j <= n);
+        // 長さ ok は一致、ng は不一致
+        let mut ok = 0;
+        let mut ng = n - i.max(j) + 1;
+        while ok + 1 < ng {
+            let m = (ok + ng) / 2;
+            if self.substring_hash(i, i + m) == self.substring_hash(j, j + m) {
+                ok = m;
+            } else {
+                ng = m;
+            }
+        }
+        ok
+    }
+
+    /// 区間 `[l1, r1)` と `[l2, r2)` の部分列を辞書順で比較します
+    /// O(log(|s|))
+    pub fn compare(&self, l1: usize, r1: usize, l2: usize, r2: usize) -> Ordering {
+        let (n1, n2) = (r1 - l1, r2 - l2);
+        let k = self.lcp(l1, l2).min(n1).min(n2);
+        if k == n1 || k == n2 {
+            n1.cmp(&n2)
+        } else {
+            self.s[l1 + k].cmp(&self.s[l2 + k])
+        }
+    }
+
+    /// 区間 `[l1, r1)` の後ろに `[l2, r2)` を連結した数列のハッシュを返します
+    /// O(1)
+    pub fn concat_hash(&self, l1: usize, r1: usize, l2: usize, r2: usize) -> u64 {
+        let h1 = self.substring_hash(l1, r1);
+        let h2 = self.substring_hash(l2, r2);
+        CalcMod(Mul(h1, self.pw[r2 - l2]) + h2)
+    }
+}
+
+/// グリッドの 2次元累積ハッシュを前計算し、部分長方形のハッシュを O(1) で求めます
+/// 行方向の基数に [`B_ROW`]、列方向の基数に [`B`] を使います
+///
+/// ## Example
+/// ```
+/// use kyopro_grid::{Grid, P};
+/// use kyopro_string::rolling_hash::GridHash;
+/// let g = Grid::from_vec(vec![
+///     vec![1u8, 2, 1, 2],
+///     vec![3, 4, 3, 4],
+///     vec![1, 2, 0, 0],
+/// ]);
+/// let gh = GridHash::new(&g);
+/// assert_eq!(gh.rect_hash(0, 2, 0, 2), gh.rect_hash(0, 2, 2, 4));
+///
+/// let pattern = Grid::from_vec(vec![vec![2u8], vec![4]]);
+/// assert_eq!(gh.find_all(&pattern), vec![P(0, 1), P(0, 3)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GridHash {
+    h: usize,
+    w: usize,
+    /// `hs[i][j]`: `[0, i) x [0, j)` のハッシュ
+    hs: Vec<Vec<u64>>,
+}
+
+impl GridHash {
+    /// O(HW) で前計算します
+    pub fn new<T: Into<u64> + Copy>(grid: &Grid<T>) -> Self {
+        let h = grid.g.len();
+        let w = if h > 0 { grid.g[0].len() } else { 0 };
+        let mut hs = vec![vec![0; w + 1]; h + 1];
+        for i in 0..h {
+            for j in 0..w {
+                let v: u64 = grid.g[i][j].into();
+                let x = CalcMod(MulMod(hs[i + 1][j], B) + MulMod(hs[i][j + 1], B_ROW));
+                let y = MulMod(MulMod(hs[i][j], B_ROW), B);
+                hs[i + 1][j + 1] = CalcMod(Sub(x, y) + v);
+            }
+        }
+        GridHash { h, w, hs }
+    }
+
+    /// 左上座標 (t, l) から 右下座標 (b, r) の範囲のハッシュを求めます (右下は半開区間)
+    /// 範囲指定順注意 [t, b), [l, r) の順で指定する
+    pub fn rect_hash(&self, t: usize, b: usize, l: usize, r: usize) -> u64 {
+        assert!(t <= b && b <= self.h && l <= r && r <= self.w);
+        let pr = get_row_bs(b - t);
+        let pc = get_bs(r - l);
+        let x = Sub(self.hs[b][r], MulMod(self.hs[t][r], pr));
+        let x = Sub(x, MulMod(self.hs[b][l], pc));
+        CalcMod(x + MulMod(MulMod(self.hs[t][l], pr), pc))
+    }
+
+    /// `pattern` と一致する部分長方形の左上座標をすべて返します
+    /// O(HW)
+    pub fn find_all<T: Into<u64> + Copy>(&self, pattern: &Grid<T>) -> Vec<P> {
+        let ph = pattern.g.len();
+        let pw = if ph > 0 { pattern.g[0].len() } else { 0 };
+        if ph > self.h || pw > self.w {
+            return vec![];
+        }
+        let target = GridHash::new(pattern).rect_hash(0, ph, 0, pw);
+        let mut res = vec![];
+        for i in 0..=self.h - ph {
+            for j in 0..=self.w - pw {
+                if self.rect_hash(i, i + ph, j, j + pw) == target {
+                    res.push(P(i, j));
+                }
+            }
+        }
+        res
+    }
+}
+
+/// [`RandomHasher`] のハッシュ値です
+/// `(mod 2^61-1 の値, mod MOD2 の値)` で、double hash でない場合 `.1` は常に 0 です
+pub type HashValue = (u64, u64);
+
+/// 基数を実行時に乱数で決める rolling hash です
+/// 固定基数 [`B`] を狙った hack への対策として使います
+///
+/// ## Example
+/// ```
+/// use kyopro_string::rolling_hash::RandomHasher;
+/// // double hash (mod 2^61-1 と mod 998244353)
+/// let hasher = RandomHasher::from_time().double();
+/// let s = [1, 2, 3, 1, 2];
+/// let pre = hasher.prefix(&s);
+/// assert_eq!(hasher.substring(&pre, 0, 2), hasher.substring(&pre, 3, 5));
+/// assert_eq!(hasher.substring(&pre, 1, 4), hasher.hash(&[2, 3, 1]));
+/// assert!(hasher.contains(&[3, 1], &s));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RandomHasher {
+    b1: u64,
+    /// double hash の場合の mod MOD2 側の基数
+    b2: Option<u64>,
+    /// `pw[i]`: 基数の i 乗
+    pw: RefCell<Vec<HashValue>>,
+}
+
+/// splitmix64
+fn next_rand(x: &mut u64) -> u64 {
+    *x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl RandomHasher {
+    /// seed から基数を決めます
+    /// 同じ seed なら同じハッシュ値になります
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        // 小さすぎる基数は避ける
+        let b1 = (1 << 20) + next_rand(&mut x) % (MOD - (1 << 21));
+        RandomHasher { b1, b2: None, pw: RefCell::new(vec![(1, 0)]) }
+    }
+
+    /// 現在時刻を seed にして基数を決めます
+    pub fn from_time() -> Self {
+        let t = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        Self::new(t.as_nanos() as u64)
+    }
+
+    /// `mod MOD2` も併用する double hash にします
+    pub fn double(mut self) -> Self {
+        if self.b2.is_none() {
+            let mut x = self.b1;
+            self.b2 = Some(2 + next_rand(&mut x) % (MOD2 - 3));
+            self.pw = RefCell::new(vec![(1, 1)]);
+        }
+        self
+    }
+
+    /// double hash かどうかを返します
+    pub fn is_double(&self) -> bool {
+        self.b2.is_some()
+    }
+
+    /// 基数の `e` 乗を返します
+    /// 償却 O(1)
+    fn pow(&self, e: usize) -> HashValue {
+        let mut pw = self.pw.borrow_mut();
+        while pw.len() <= e {
+            let &(p1, p2) = pw.last().unwrap();
+            let p2 = self.b2.map_or(0, |b2| p2 * b2 % MOD2);
+            pw.push((MulMod(p1, self.b1), p2));
+        }
+        pw[e]
+    }
+
+    /// 数列 `s` のハッシュを取得します
+    pub fn hash(&self, s: &[u64]) -> HashValue {
+        s.iter().fold((0, 0), |h, &v| self.push_back(h, v))
+    }
+
+    /// 末尾に `v` を追加したハッシュを取得します
+    pub fn push_back(&self, h: HashValue, v: u64) -> HashValue {
+        let h1 = CalcMod(Mul(h.0, self.b1) + v);
+        let h2 = self.b2.map_or(0, |b2| (h.1 * b2 + v % MOD2) % MOD2);
+        (h1, h2)
+    }
+
+    /// 先頭に `v` を追加したハッシュを取得します
+    /// + `current_len`: 現在の数列の 長さ
+    pub fn push_front(&self, h: HashValue, current_len: usize, v: u64) -> HashValue {
+        let p = self.pow(current_len);
+        (CalcMod(h.0 + Mul(v, p.0)), (h.1 + v % MOD2 * p.1) % MOD2)
+    }
+
+    /// 数列 `s` の後ろに長さ `len_t` の数列 `t` を連結したハッシュを取得します
+    pub fn concat(&self, hs: HashValue, ht: HashValue, len_t: usize) -> HashValue {
+        let p = self.pow(len_t);
+        (CalcMod(Mul(hs.0, p.0) + ht.0), (hs.1 * p.1 + ht.1) % MOD2)
+    }
+
+    /// 文字列などのハッシュを取得します
+    pub fn hash_of<S: ToHashSeq + ?Sized>(&self, s: &S) -> HashValue {
+        self.hash(&s.to_hash_seq())
+    }
+
+    /// 累積ハッシュを返します
+    /// 戻り値の `i` 番目は `s[0..i]` のハッシュです
+    pub fn prefix(&self, s: &[u64]) -> Vec<HashValue> {
+        let mut res = Vec::with_capacity(s.len() + 1);
+        res.push((0, 0));
+        for &v in s {
+            res.push(self.push_back(*res.last().unwrap(), v));
+        }
+        res
+    }
+
+    /// 累積ハッシュ `prefix` から区間 `[l, r)` のハッシュを求めます
+    /// O(1)
+    pub fn substring(&self, prefix: &[HashValue], l: usize, r: usize) -> HashValue {
+        let p = self.pow(r - l);
+        let (a, b) = (prefix[r], prefix[l]);
+        (Sub(a.0, MulMod(b.0, p.0)), (a.1 + MOD2 - b.1 * p.1 % MOD2) % MOD2)
+    }
+
+    /// a は b に含まれているか? を返します。
+    /// O(|a|+|b|)
+    pub fn contains(&self, a: &[u64], b: &[u64]) -> bool {
+        if a.len() > b.len() {
+            return false;
+        }
+        let ah = self.hash(a);
+        let pre = self.prefix(b);
+        (0..=b.len() - a.len()).any(|i| self.substring(&pre, i, i + a.len()) == ah)
+    }
+}
+
+#[test]
+fn test_rolling_hash() {
+    let a = [2, 100, 5005];
+    let ah = hash(&a);
+    {
+        let mut h = 0;
+        for &v in &a {
+            h = push_back(h, v);
+        }
+        assert_eq!(ah, h);
+    }
+    {
+        let mut h = 0;
+        for (i, &v) in a.iter().rev().enumerate() {
+            h = push_front(h, i, v);
+        }
+        assert_eq!(ah, h);
+    }
+}
+
+#[test]
+fn test_rolling_hash_contains() {
+    let a = [2, 100, 5005];
+    let b_true1 = [1, 2, 2, 100, 5005];
+    let b_true2 = [2, 100, 5005, 1, 2];
+    let b_false1 = [2, 100, 5004];
+    let b_false2 = [1, 100, 5005];
+    let b_false3 = [2, 100];
+    assert!(contains(&a, &a));
+    assert!(contains(&a, &b_true1));
+    assert!(contains(&a, &b_true2));
+    assert!(!contains(&a, &b_false1));
+    assert!(!contains(&a, &b_false2));
+    assert!(!contains(&a, &b_false3));
+}
+
+#[test]
+fn test_substring_hash() {
+    let s = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+    let rh = RollingHash::new(&s);
+    for l in 0..=s.len() {
+        for r in l..=s.len() {
+            assert_eq!(rh.substring_hash(l, r), hash(&s[l..r]));
+        }
+    }
+}
+
+#[test]
+fn test_lcp_and_compare() {
+    let s = [1, 2, 3, 1, 2, 4, 1, 2, 3];
+    let rh = RollingHash::new(&s);
+    assert_eq!(rh.lcp(0, 3), 2);
+    assert_eq!(rh.lcp(0, 6), 3);
+    assert_eq!(rh.lcp(2, 2), 7);
+    assert_eq!(rh.lcp(0, 9), 0);
+    for l1 in 0..s.len() {
+        for r1 in l1..=s.len() {
+            for l2 in 0..s.len() {
+                for r2 in l2..=s.len() {
+                    assert_eq!(rh.compare(l1, r1, l2, r2), s[l1..r1].cmp(&s[l2..r2]));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_concat_hash() {
+    let s = [5, 7, 2, 8, 1];
+    let rh = RollingHash::new(&s);
+    assert_eq!(rh.concat_hash(3, 5, 0, 2), hash(&[8, 1, 5, 7]));
+    assert_eq!(rh.concat_hash(1, 1, 0, 2), hash(&[5, 7]));
+    assert_eq!(concat(hash(&s[..2]), hash(&s[2..]), 3), hash(&s));
+}
+
+#[test]
+fn test_get_bs_grows() {
+    assert_eq!(get_bs(0), 1);
+    assert_eq!(get_bs(300_000), CalcPow(B, 300_000));
+    assert_eq!(get_bs(12345), CalcPow(B, 12345));
+    assert_eq!(push_front(0, 250_000, 1), CalcPow(B, 250_000));
+    // 要求された長さまで伸長し、それ以上は確保しない
+    assert_eq!(BS.with(|bs| bs.borrow().len()), 300_001);
+}
+
+#[test]
+fn test_random_hasher() {
+    for hasher in [RandomHasher::new(1), RandomHasher::new(2).double(), RandomHasher::from_time()] {
+        let s = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let pre = hasher.prefix(&s);
+        for l in 0..=s.len() {
+            for r in l..=s.len() {
+                let h = hasher.substring(&pre, l, r);
+                assert_eq!(h, hasher.hash(&s[l..r]));
+                assert_eq!(hasher.concat(hasher.hash(&s[..l]), h, r - l), hasher.hash(&s[..r]));
+            }
+        }
+        let mut h = (0, 0);
+        for (i, &v) in s.iter().rev().enumerate() {
+            h = hasher.push_front(h, i, v);
+        }
+        assert_eq!(h, hasher.hash(&s));
+        assert!(hasher.contains(&[1, 5, 9], &s));
+        assert!(!hasher.contains(&[1, 5, 8], &s));
+    }
+}
+
+#[test]
+fn test_random_hasher_seed() {
+    let a = RandomHasher::new(42);
+    let b = RandomHasher::new(42);
+    let c = RandomHasher::new(43);
+    assert_eq!(a.hash(&[1, 2, 3]), b.hash(&[1, 2, 3]));
+    assert_ne!(a.hash(&[1, 2, 3]), c.hash(&[1, 2, 3]));
+    assert!(!a.is_double());
+    assert_eq!(a.hash(&[1, 2, 3]).1, 0);
+    assert!(a.double().is_double());
+}
+
+#[test]
+fn test_hash_of() {
+    let chars: Vec<char> = "hello".chars().collect();
+    let bytes = b"hello";
+    assert_eq!(hash_of("hello"), hash_of(&chars));
+    assert_eq!(hash_of("hello"), hash_of(&bytes[..]));
+    assert_eq!(hash_of(&"hello".to_string()), hash(&[104, 101, 108, 108, 111]));
+    assert_eq!(hash_of(&vec![1u32, 2, 3]), hash(&[1, 2, 3]));
+    assert!(contains_of("ell", "hello"));
+    assert!(!contains_of("elo", &chars));
+    let rh = RollingHash::from_seq(&chars);
+    assert_eq!(rh.substring_hash(2, 4), hash_of("ll"));
+    let hasher = RandomHasher::new(7);
+    assert_eq!(hasher.hash_of("ll"), hasher.hash_of(&chars[2..4]));
+    // 非 ASCII の文字列もコードポイントでハッシュする
+    let chars: Vec<char> = "café日本".chars().collect();
+    assert_eq!(hash_of("café日本"), hash_of(&chars));
+    assert_eq!(hash_of("é"), hash(&['é' as u64]));
+    assert!(contains_of("é日", &chars));
+}
+
+#[test]
+fn test_grid_hash() {
+    let g = Grid::from_vec(vec![
+        vec![1u32, 2, 3, 1, 2],
+        vec![4, 5, 6, 4, 5],
+        vec![1, 2, 3, 1, 2],
+        vec![4, 5, 6, 4, 9],
+    ]);
+    let gh = GridHash::new(&g);
+    // 愚直に一致判定した結果と比較する
+    for t in 0..=4 {
+        for b in t..=4 {
+            for l in 0..=5 {
+                for r in l..=5 {
+                    for t2 in 0..=4 - (b - t) {
+                        for l2 in 0..=5 - (r - l) {
+                            let same = (0..b - t).all(|i| (0..r - l).all(|j| g[t + i][l + j] == g[t2 + i][l2 + j]));
+                            let eq = gh.rect_hash(t, b, l, r) == gh.rect_hash(t2, t2 + b - t, l2, l2 + r - l);
+                            assert_eq!(same, eq);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let pattern = Grid::from_vec(vec![vec![1u32, 2], vec![4, 5]]);
+    assert_eq!(gh.find_all(&pattern), vec![P(0, 0), P(0, 3), P(2, 0)]);
+    let pattern = Grid::from_vec(vec![vec![7u32; 6]]);
+    assert_eq!(gh.find_all(&pattern), vec![]);
+}
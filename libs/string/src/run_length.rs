@@ -0,0 +1,273 @@
+//! ランレングス圧縮
+//!
+//! ## Example
+//! ```
+//! use kyopro_string::run_length::{run_length, run_length_decode};
+//! let runs: Vec<(char, usize)> = run_length("aaabcc".chars()).collect();
+//! assert_eq!(runs, vec![('a', 3), ('b', 1), ('c', 2)]);
+//! let s: String = run_length_decode(runs).collect();
+//! assert_eq!(s, "aaabcc");
+//! ```
+use std::{iter::Peekable, ops::Index};
+
+/// 連続する等しい要素を `(要素, 個数)` にまとめる iterator です
+/// [`run_length`] で作成します
+pub struct RunLength<I: Iterator> {
+    iter: Peekable<I>,
+}
+
+impl<I> Iterator for RunLength<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = (I::Item, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.iter.next()?;
+        let mut cnt = 1;
+        while self.iter.next_if(|x| *x == v).is_some() {
+            cnt += 1;
+        }
+        Some((v, cnt))
+    }
+}
+
+/// ランレングス圧縮した iterator を返します
+/// 各連続部分の先頭の要素が返されます
+pub fn run_length<I>(iter: I) -> RunLength<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: PartialEq,
+{
+    RunLength { iter: iter.into_iter().peekable() }
+}
+
+/// ランレングス圧縮された列を復元した iterator を返します
+pub fn run_length_decode<T, I>(runs: I) -> impl Iterator<Item = T>
+where
+    T: Clone,
+    I: IntoIterator<Item = (T, usize)>,
+{
+    runs.into_iter().flat_map(|(v, cnt)| std::iter::repeat(v).take(cnt))
+}
+
+/// key が連続して等しい要素をまとめる iterator です
+/// [`run_length_by`] で作成します
+pub struct RunLengthBy<I: Iterator, F> {
+    iter: Peekable<I>,
+    key: F,
+}
+
+impl<I, K, F> Iterator for RunLengthBy<I, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, Vec<I::Item>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.iter.next()?;
+        let k = (self.key)(&v);
+        let mut group = vec![v];
+        while let Some(x) = self.iter.peek() {
+            if (self.key)(x) != k {
+                break;
+            }
+            group.push(self.iter.next().unwrap());
+        }
+        Some((k, group))
+    }
+}
+
+/// key が連続して等しい要素を `(key, 要素の vector)` にまとめた iterator を返します
+/// ```
+/// use kyopro_string::run_length::run_length_by;
+/// let groups: Vec<_> = run_length_by(vec![1, 3, 2, 4, 5], |x| x % 2).collect();
+/// assert_eq!(groups, vec![(1, vec![1, 3]), (0, vec![2, 4]), (1, vec![5])]);
+/// ```
+pub fn run_length_by<I, K, F>(iter: I, key: F) -> RunLengthBy<I::IntoIter, F>
+where
+    I: IntoIterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    RunLengthBy { iter: iter.into_iter().peekable(), key }
+}
+
+/// ランレングス圧縮した状態で列を保持します
+/// 添字アクセスは O(log(連続部分の個数)) です
+///
+/// ## Example
+/// ```
+/// use kyopro_string::run_length::RunLengthVec;
+/// let rl: RunLengthVec<char> = "aaabcc".chars().collect();
+/// assert_eq!(rl.len(), 6);
+/// assert_eq!(rl[3], 'b');
+/// let (l, r) = rl.split_at(2);
+/// assert_eq!(l.runs(), &[('a', 2)]);
+/// assert_eq!(r.runs(), &[('a', 1), ('b', 1), ('c', 2)]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunLengthVec<T> {
+    runs: Vec<(T, usize)>,
+    /// `ends[i]`: `runs[..=i]` の要素数の合計
+    ends: Vec<usize>,
+}
+
+impl<T: PartialEq> RunLengthVec<T> {
+    pub fn new() -> Self {
+        RunLengthVec { runs: vec![], ends: vec![] }
+    }
+
+    /// `(要素, 個数)` の列から作成します
+    /// 隣り合う等しい要素や個数 0 の要素はまとめられます
+    pub fn from_runs<I: IntoIterator<Item = (T, usize)>>(runs: I) -> Self {
+        let mut res = Self::new();
+        for (v, cnt) in runs {
+            res.push_run(v, cnt);
+        }
+        res
+    }
+
+    /// 末尾に `v` を `cnt` 個追加します
+    pub fn push_run(&mut self, v: T, cnt: usize) {
+        if cnt == 0 {
+            return;
+        }
+        let len = self.len();
+        match self.runs.last_mut() {
+            Some((last, c)) if *last == v => {
+                *c += cnt;
+                *self.ends.last_mut().unwrap() += cnt;
+            }
+            _ => {
+                self.runs.push((v, cnt));
+                self.ends.push(len + cnt);
+            }
+        }
+    }
+
+    /// 末尾に `v` を追加します
+    pub fn push(&mut self, v: T) {
+        self.push_run(v, 1);
+    }
+
+    /// 要素数を返します
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// 圧縮された `(要素, 個数)` の列を返します
+    pub fn runs(&self) -> &[(T, usize)] {
+        &self.runs
+    }
+
+    /// `i` 番目の要素を含む連続部分の index を返します
+    pub fn run_index(&self, i: usize) -> usize {
+        assert!(i < self.len());
+        self.ends.partition_point(|&e| e <= i)
+    }
+
+    /// `i` 番目の要素を返します
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i < self.len() {
+            Some(&self.runs[self.run_index(i)].0)
+        } else {
+            None
+        }
+    }
+
+    /// 要素を先頭から順に返す iterator を返します
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.runs.iter().flat_map(|(v, cnt)| std::iter::repeat(v).take(*cnt))
+    }
+}
+
+impl<T: PartialEq + Clone> RunLengthVec<T> {
+    /// `[0, pos)` と `[pos, len)` に分割します
+    pub fn split_at(&self, pos: usize) -> (Self, Self) {
+        assert!(pos <= self.len());
+        let mut l = Self::new();
+        let mut r = Self::new();
+        let mut start = 0;
+        for (i, (v, cnt)) in self.runs.iter().enumerate() {
+            let end = self.ends[i];
+            if end <= pos {
+                l.push_run(v.clone(), *cnt);
+            } else if pos <= start {
+                r.push_run(v.clone(), *cnt);
+            } else {
+                l.push_run(v.clone(), pos - start);
+                r.push_run(v.clone(), end - pos);
+            }
+            start = end;
+        }
+        (l, r)
+    }
+
+    /// 元の列を vector で返します
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> Default for RunLengthVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq> FromIterator<T> for RunLengthVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_runs(run_length(iter))
+    }
+}
+
+impl<T: PartialEq> Index<usize> for RunLengthVec<T> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {
+        self.get(i).expect("index out of bounds")
+    }
+}
+
+#[test]
+fn test_run_length() {
+    let v = vec![1, 1, 2, 2, 2, 1, 3];
+    let runs: Vec<_> = run_length(v.iter()).map(|(x, c)| (*x, c)).collect();
+    assert_eq!(runs, vec![(1, 2), (2, 3), (1, 1), (3, 1)]);
+    assert_eq!(run_length_decode(runs).collect::<Vec<_>>(), v);
+    assert_eq!(run_length(Vec::<i32>::new()).count(), 0);
+    assert_eq!(run_length_decode(Vec::<(i32, usize)>::new()).count(), 0);
+}
+
+#[test]
+fn test_run_length_by() {
+    let words = ["apple", "avocado", "banana", "blueberry", "cherry", "apricot"];
+    let groups: Vec<_> = run_length_by(words, |w| w.as_bytes()[0]).map(|(k, g)| (k, g.len())).collect();
+    assert_eq!(groups, vec![(b'a', 2), (b'b', 2), (b'c', 1), (b'a', 1)]);
+}
+
+#[test]
+fn test_run_length_vec() {
+    let v = vec![5, 5, 5, 2, 7, 7, 5];
+    let rl: RunLengthVec<i32> = v.iter().copied().collect();
+    assert_eq!(rl.len(), v.len());
+    assert_eq!(rl.runs(), &[(5, 3), (2, 1), (7, 2), (5, 1)]);
+    for (i, x) in v.iter().enumerate() {
+        assert_eq!(rl[i], *x);
+    }
+    assert_eq!(rl.get(v.len()), None);
+    assert_eq!(rl.to_vec(), v);
+    for pos in 0..=v.len() {
+        let (l, r) = rl.split_at(pos);
+        assert_eq!(l.to_vec(), v[..pos]);
+        assert_eq!(r.to_vec(), v[pos..]);
+    }
+    let rl2 = RunLengthVec::from_runs(vec![(1, 2), (1, 0), (1, 3), (2, 0)]);
+    assert_eq!(rl2.runs(), &[(1, 5)]);
+    assert!(RunLengthVec::<i32>::new().is_empty());
+}
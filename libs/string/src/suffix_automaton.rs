@@ -0,0 +1,260 @@
+//! Suffix Automaton
+//! 文字列のすべての部分文字列を受理する最小の DFA です
+//!
+//! 文字は `0..sigma` の整数で表します。
+//!
+//! ## Example
+//! ```
+//! use kyopro_string::suffix_automaton::SuffixAutomaton;
+//! let s: Vec<usize> = "abcbc".bytes().map(|c| (c - b'a') as usize).collect();
+//! let mut sa = SuffixAutomaton::new(26);
+//! for &c in &s {
+//!     sa.extend(c);
+//! }
+//! // a, b, c, ab, bc, cb, abc, bcb, cbc, abcb, bcbc, abcbc
+//! assert_eq!(sa.distinct_substrings(), 12);
+//!
+//! // "bc" の出現回数
+//! let cnt = sa.occurrence_counts();
+//! assert_eq!(sa.state_of(&[1, 2]).map(|v| cnt[v]), Some(2));
+//!
+//! // 辞書順で 3 番目 (0-indexed) の部分文字列は "abcb"
+//! assert_eq!(sa.kth_substring(3), Some(vec![0, 1, 2, 1]));
+//! ```
+
+/// 遷移先が存在しないことを表す値
+const NONE: usize = !0;
+
+#[derive(Clone, Debug)]
+pub struct SuffixAutomaton {
+    sigma: usize,
+    next: Vec<Vec<usize>>,
+    /// suffix link
+    link: Vec<usize>,
+    /// 状態が表す文字列の最大長
+    len: Vec<usize>,
+    /// 状態が複製でない (ある prefix の終端である) か
+    is_prefix: Vec<bool>,
+    /// 文字列全体に対応する状態
+    last: usize,
+}
+
+impl SuffixAutomaton {
+    /// + `sigma`: 文字種数
+    pub fn new(sigma: usize) -> Self {
+        SuffixAutomaton {
+            sigma,
+            next: vec![vec![NONE; sigma]],
+            link: vec![NONE],
+            len: vec![0],
+            is_prefix: vec![false],
+            last: 0,
+        }
+    }
+
+    fn new_state(&mut self, len: usize, is_prefix: bool) -> usize {
+        self.next.push(vec![NONE; self.sigma]);
+        self.link.push(NONE);
+        self.len.push(len);
+        self.is_prefix.push(is_prefix);
+        self.next.len() - 1
+    }
+
+    /// 末尾に文字 `c` を追加します
+    /// 償却 O(sigma)
+    pub fn extend(&mut self, c: usize) {
+        assert!(c < self.sigma);
+        let cur = self.new_state(self.len[self.last] + 1, true);
+        let mut p = self.last;
+        while p != NONE && self.next[p][c] == NONE {
+            self.next[p][c] = cur;
+            p = self.link[p];
+        }
+        if p == NONE {
+            self.link[cur] = 0;
+        } else {
+            let q = self.next[p][c];
+            if self.len[p] + 1 == self.len[q] {
+                self.link[cur] = q;
+            } else {
+                // q を複製して長さを揃える
+                let clone = self.new_state(self.len[p] + 1, false);
+                self.next[clone] = self.next[q].clone();
+                self.link[clone] = self.link[q];
+                while p != NONE && self.next[p][c] == q {
+                    self.next[p][c] = clone;
+                    p = self.link[p];
+                }
+                self.link[q] = clone;
+                self.link[cur] = clone;
+            }
+        }
+        self.last = cur;
+    }
+
+    /// 状態数を返します
+    pub fn len(&self) -> usize {
+        self.next.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.next.is_empty()
+    }
+
+    /// 状態 `v` から文字 `c` で遷移した先の状態を返します
+    /// 遷移先がない場合は None です
+    pub fn next(&self, v: usize, c: usize) -> Option<usize> {
+        let u = self.next[v][c];
+        if u == NONE {
+            None
+        } else {
+            Some(u)
+        }
+    }
+
+    /// 状態 `v` の suffix link を返します
+    /// 初期状態 0 の場合は None です
+    pub fn link(&self, v: usize) -> Option<usize> {
+        let u = self.link[v];
+        if u == NONE {
+            None
+        } else {
+            Some(u)
+        }
+    }
+
+    /// `pattern` を読んだ後の状態を返します
+    /// `pattern` が部分文字列でない場合は None です
+    pub fn state_of(&self, pattern: &[usize]) -> Option<usize> {
+        pattern.iter().try_fold(0, |v, &c| self.next(v, c))
+    }
+
+    /// `pattern` が部分文字列かどうかを返します
+    pub fn contains(&self, pattern: &[usize]) -> bool {
+        self.state_of(pattern).is_some()
+    }
+
+    /// 相異なる空でない部分文字列の個数を返します
+    pub fn distinct_substrings(&self) -> usize {
+        (1..self.len()).map(|v| self.len[v] - self.len[self.link[v]]).sum()
+    }
+
+    /// 状態を len の降順に並べたものを返します
+    fn order_by_len_desc(&self) -> Vec<usize> {
+        let mut bucket = vec![0; self.len[self.last] + 2];
+        for &l in &self.len {
+            bucket[l + 1] += 1;
+        }
+        for i in 1..bucket.len() {
+            bucket[i] += bucket[i - 1];
+        }
+        let mut order = vec![0; self.len()];
+        for v in 0..self.len() {
+            let l = self.len[v];
+            order[bucket[l]] = v;
+            bucket[l] += 1;
+        }
+        order.reverse();
+        order
+    }
+
+    /// 各状態が表す部分文字列の出現回数を返します
+    /// O(状態数)
+    pub fn occurrence_counts(&self) -> Vec<usize> {
+        let mut cnt: Vec<usize> = self.is_prefix.iter().map(|&b| b as usize).collect();
+        for v in self.order_by_len_desc() {
+            if v != 0 {
+                cnt[self.link[v]] += cnt[v];
+            }
+        }
+        cnt
+    }
+
+    /// 相異なる部分文字列のうち辞書順で `k` 番目 (0-indexed) のものを返します
+    /// 存在しない場合は None です
+    /// O(状態数 * sigma + 答えの長さ * sigma)
+    pub fn kth_substring(&self, k: usize) -> Option<Vec<usize>> {
+        // paths[v]: 状態 v から始まる空でない文字列の個数
+        let mut paths = vec![0usize; self.len()];
+        for v in self.order_by_len_desc() {
+            paths[v] = self.next[v]
+                .iter()
+                .filter(|&&u| u != NONE)
+                .fold(0usize, |s, &u| s.saturating_add(paths[u].saturating_add(1)));
+        }
+        if k >= paths[0] {
+            return None;
+        }
+        let mut k = k;
+        let mut v = 0;
+        let mut res = vec![];
+        loop {
+            for c in 0..self.sigma {
+                let u = self.next[v][c];
+                if u == NONE {
+                    continue;
+                }
+                let sub = paths[u].saturating_add(1);
+                if k < sub {
+                    res.push(c);
+                    v = u;
+                    break;
+                }
+                k -= sub;
+            }
+            if k == 0 {
+                return Some(res);
+            }
+            // 現在の文字列自身の分
+            k -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SuffixAutomaton;
+    use std::collections::{BTreeSet, HashMap};
+
+    fn build(s: &[usize], sigma: usize) -> SuffixAutomaton {
+        let mut sa = SuffixAutomaton::new(sigma);
+        for &c in s {
+            sa.extend(c);
+        }
+        sa
+    }
+
+    #[test]
+    fn test_naive() {
+        let s = [0, 1, 0, 0, 1, 0, 1, 1, 0, 2, 0, 1];
+        let sa = build(&s, 3);
+        let mut subs = BTreeSet::new();
+        let mut occ = HashMap::new();
+        for l in 0..s.len() {
+            for r in l + 1..=s.len() {
+                subs.insert(s[l..r].to_vec());
+                *occ.entry(s[l..r].to_vec()).or_insert(0) += 1;
+            }
+        }
+        assert_eq!(sa.distinct_substrings(), subs.len());
+
+        let cnt = sa.occurrence_counts();
+        for (t, &c) in &occ {
+            assert_eq!(sa.state_of(t).map(|v| cnt[v]), Some(c));
+        }
+        assert!(!sa.contains(&[2, 2]));
+
+        for (k, t) in subs.iter().enumerate() {
+            assert_eq!(sa.kth_substring(k).as_ref(), Some(t));
+        }
+        assert_eq!(sa.kth_substring(subs.len()), None);
+    }
+
+    #[test]
+    fn test_empty() {
+        let sa = SuffixAutomaton::new(2);
+        assert_eq!(sa.distinct_substrings(), 0);
+        assert_eq!(sa.kth_substring(0), None);
+        assert!(sa.contains(&[]));
+    }
+}
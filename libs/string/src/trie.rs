@@ -0,0 +1,190 @@
+//! Trie 木
+//! 文字列の多重集合を管理します
+//!
+//! 文字は `0..sigma` の整数で表します。
+//! 文字列は `*_str` 系のメソッドで `first` を 0 とした整数列に変換して扱えます。
+//!
+//! ## Example
+//! ```
+//! use kyopro_string::trie::Trie;
+//! let mut trie = Trie::new(26);
+//! trie.insert_str("apple", b'a');
+//! trie.insert_str("app", b'a');
+//! trie.insert_str("banana", b'a');
+//! assert_eq!(trie.prefix_count_str("ap", b'a'), 2);
+//! assert_eq!(trie.count_str("app", b'a'), 1);
+//! // 辞書順で 1 番目 (0-indexed) の文字列は "apple"
+//! assert_eq!(trie.kth(1), Some("apple".bytes().map(|c| (c - b'a') as usize).collect()));
+//! ```
+
+/// 遷移先が存在しないことを表す値
+const NONE: usize = !0;
+
+#[derive(Clone, Debug)]
+pub struct Trie {
+    sigma: usize,
+    next: Vec<Vec<usize>>,
+    /// ノードを通る文字列の個数
+    pass: Vec<usize>,
+    /// ノードで終わる文字列の個数
+    end: Vec<usize>,
+}
+
+fn to_seq(s: &str, first: u8) -> Vec<usize> {
+    s.bytes().map(|c| (c - first) as usize).collect()
+}
+
+impl Trie {
+    /// + `sigma`: 文字種数
+    pub fn new(sigma: usize) -> Self {
+        Trie { sigma, next: vec![vec![NONE; sigma]], pass: vec![0], end: vec![0] }
+    }
+
+    /// `s` のノードを返します
+    fn find(&self, s: &[usize]) -> Option<usize> {
+        let mut v = 0;
+        for &c in s {
+            v = self.next[v][c];
+            if v == NONE {
+                return None;
+            }
+        }
+        Some(v)
+    }
+
+    /// 登録されている文字列の個数を返します
+    pub fn len(&self) -> usize {
+        self.pass[0]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 文字列 `s` を追加します
+    /// O(|s|)
+    pub fn insert(&mut self, s: &[usize]) {
+        let mut v = 0;
+        self.pass[0] += 1;
+        for &c in s {
+            assert!(c < self.sigma);
+            if self.next[v][c] == NONE {
+                self.next[v][c] = self.next.len();
+                self.next.push(vec![NONE; self.sigma]);
+                self.pass.push(0);
+                self.end.push(0);
+            }
+            v = self.next[v][c];
+            self.pass[v] += 1;
+        }
+        self.end[v] += 1;
+    }
+
+    /// 文字列 `s` を 1 つ削除します
+    /// 存在しない場合は何もせず false を返します
+    /// O(|s|)
+    pub fn erase(&mut self, s: &[usize]) -> bool {
+        if self.count(s) == 0 {
+            return false;
+        }
+        let mut v = 0;
+        self.pass[0] -= 1;
+        for &c in s {
+            v = self.next[v][c];
+            self.pass[v] -= 1;
+        }
+        self.end[v] -= 1;
+        true
+    }
+
+    /// 文字列 `s` の個数を返します
+    pub fn count(&self, s: &[usize]) -> usize {
+        self.find(s).map_or(0, |v| self.end[v])
+    }
+
+    /// `prefix` で始まる文字列の個数を返します
+    pub fn prefix_count(&self, prefix: &[usize]) -> usize {
+        self.find(prefix).map_or(0, |v| self.pass[v])
+    }
+
+    /// 辞書順で `k` 番目 (0-indexed) の文字列を返します
+    /// 存在しない場合は None です
+    /// O(答えの長さ * sigma)
+    pub fn kth(&self, k: usize) -> Option<Vec<usize>> {
+        if k >= self.len() {
+            return None;
+        }
+        let mut k = k;
+        let mut v = 0;
+        let mut res = vec![];
+        loop {
+            if k < self.end[v] {
+                return Some(res);
+            }
+            k -= self.end[v];
+            for c in 0..self.sigma {
+                let u = self.next[v][c];
+                if u == NONE {
+                    continue;
+                }
+                if k < self.pass[u] {
+                    res.push(c);
+                    v = u;
+                    break;
+                }
+                k -= self.pass[u];
+            }
+        }
+    }
+
+    /// 文字列 `s` を追加します
+    /// + `first`: 文字 `0` として扱う文字 (`b'a'` など)
+    pub fn insert_str(&mut self, s: &str, first: u8) {
+        self.insert(&to_seq(s, first));
+    }
+
+    /// 文字列 `s` を 1 つ削除します
+    pub fn erase_str(&mut self, s: &str, first: u8) -> bool {
+        self.erase(&to_seq(s, first))
+    }
+
+    /// 文字列 `s` の個数を返します
+    pub fn count_str(&self, s: &str, first: u8) -> usize {
+        self.count(&to_seq(s, first))
+    }
+
+    /// `prefix` で始まる文字列の個数を返します
+    pub fn prefix_count_str(&self, prefix: &str, first: u8) -> usize {
+        self.prefix_count(&to_seq(prefix, first))
+    }
+}
+
+#[test]
+fn test_trie() {
+    let words = ["ab", "abc", "b", "ab", "", "ba", "abd"];
+    let mut trie = Trie::new(4);
+    for w in &words {
+        trie.insert_str(w, b'a');
+    }
+    assert_eq!(trie.len(), 7);
+    assert_eq!(trie.count_str("ab", b'a'), 2);
+    assert_eq!(trie.count_str("a", b'a'), 0);
+    assert_eq!(trie.prefix_count_str("ab", b'a'), 4);
+    assert_eq!(trie.prefix_count_str("", b'a'), 7);
+    assert_eq!(trie.prefix_count_str("c", b'a'), 0);
+
+    let mut sorted: Vec<Vec<usize>> = words.iter().map(|w| to_seq(w, b'a')).collect();
+    sorted.sort();
+    for (k, w) in sorted.iter().enumerate() {
+        assert_eq!(trie.kth(k).as_ref(), Some(w));
+    }
+    assert_eq!(trie.kth(7), None);
+
+    assert!(trie.erase_str("ab", b'a'));
+    assert!(trie.erase_str("ab", b'a'));
+    assert!(!trie.erase_str("ab", b'a'));
+    assert!(!trie.erase_str("a", b'a'));
+    assert_eq!(trie.prefix_count_str("ab", b'a'), 2);
+    assert_eq!(trie.kth(1), Some(to_seq("abc", b'a')));
+    assert_eq!(trie.len(), 5);
+}
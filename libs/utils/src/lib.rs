@@ -211,3 +211,26 @@ impl<T: PartialOrd> Ord for OrdF<T> {
         self.0.partial_cmp(&other.0).unwrap()
     }
 }
+
+/// xorshift による疑似乱数です
+/// 同じ seed からは同じ列になるので、テストのランダムケースの生成に使います
+#[derive(Clone, Debug)]
+pub struct XorShift(u64);
+
+impl XorShift {
+    /// seed には 0 以外を指定します
+    pub fn new(seed: u64) -> Self {
+        assert!(seed != 0);
+        XorShift(seed)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    /// [0, m) の乱数を返します
+    pub fn rand(&mut self, m: u64) -> u64 {
+        self.next_u64() % m
+    }
+}